@@ -1,4 +1,220 @@
+use std::{collections::HashSet, env, fmt::Write, fs, path::{Path, PathBuf}};
+
+/// Where the Tracy client comes from.
+enum Client {
+    /// Client sources to compile; the directory containing TracyClient.cpp.
+    Source(PathBuf),
+    /// An already linked, prebuilt client; the include directories are only needed for src/shim.cpp.
+    Prebuilt(Vec<PathBuf>),
+}
+
+/// Tracy 0.9 moved the client sources into public/.
+fn client_root(dir: &Path) -> Option<PathBuf> {
+    [dir.to_path_buf(), dir.join("public")].iter()
+        .find(|dir| dir.join("TracyClient.cpp").is_file())
+        .cloned()
+}
+
+/// The client sources from `TRACY_CLIENT_SOURCE_DIR`, or the ones bundled in ./tracy.
+fn client_sources() -> PathBuf {
+    println!("cargo:rerun-if-env-changed=TRACY_CLIENT_SOURCE_DIR");
+    match env::var_os("TRACY_CLIENT_SOURCE_DIR") {
+        Some(dir) => {
+            let dir = PathBuf::from(dir);
+            client_root(&dir).unwrap_or_else(|| panic!(
+                "TRACY_CLIENT_SOURCE_DIR is set to {}, which does not contain the Tracy client sources",
+                dir.display(),
+            ))
+        },
+        None => client_root(Path::new("./tracy")).unwrap_or_else(|| panic!(
+            "the Tracy client sources are missing; run `git submodule update --init`, \
+             or set TRACY_CLIENT_SOURCE_DIR or TRACY_CLIENT_LIB",
+        )),
+    }
+}
+
+/// Links the library at `TRACY_CLIENT_LIB`, which can either be the library file itself or the
+/// directory containing libTracyClient.
+fn link_prebuilt(lib: &Path) {
+    // the target, not the host this build script runs on
+    let msvc = env::var("CARGO_CFG_TARGET_ENV").unwrap() == "msvc";
+    if lib.is_dir() {
+        println!("cargo:rustc-link-search=native={}", lib.display());
+        println!("cargo:rustc-link-lib=TracyClient");
+        return;
+    }
+
+    let file_name = lib.file_name().and_then(|name| name.to_str())
+        .unwrap_or_else(|| panic!("TRACY_CLIENT_LIB is set to {}, which is not a library", lib.display()));
+    println!("cargo:rustc-link-search=native={}", lib.parent().unwrap_or_else(|| Path::new(".")).display());
+    // a versioned name like libTracyClient.so.0.11.1 can't be found through the library name
+    if file_name.contains(".so.") {
+        println!("cargo:rustc-link-lib=dylib:+verbatim={}", file_name);
+        return;
+    }
+
+    let (stem, ext) = file_name.rsplit_once('.')
+        .unwrap_or_else(|| panic!("TRACY_CLIENT_LIB is set to {}, which is not a library", lib.display()));
+    let name = if msvc { stem } else { stem.strip_prefix("lib").unwrap_or(stem) };
+    let static_lib = ext == "a" || (msvc && ext == "lib");
+    println!("cargo:rustc-link-lib={}{}", if static_lib { "static=" } else { "" }, name);
+}
+
+fn find_client() -> Client {
+    // src/testing.rs implements the C API instead; the headers are still needed to check the version
+    if cfg!(feature = "test_backend") {
+        return Client::Prebuilt(vec![client_sources()]);
+    }
+
+    println!("cargo:rerun-if-env-changed=TRACY_CLIENT_LIB");
+    if let Some(lib) = env::var_os("TRACY_CLIENT_LIB") {
+        link_prebuilt(Path::new(&lib));
+        return Client::Prebuilt(vec![client_sources()]);
+    }
+
+    #[cfg(feature = "pkg_config")]
+    {
+        let lib = pkg_config::Config::new().probe("tracy")
+            .unwrap_or_else(|err| panic!("could not find the Tracy client with pkg-config: {}", err));
+        return Client::Prebuilt(lib.include_paths);
+    }
+
+    #[allow(unreachable_code)]
+    Client::Source(client_sources())
+}
+
+/// Versions of the Tracy client the bundled bindings in src/sys/bindings.rs match, as
+/// (major, minor).
+const SUPPORTED_VERSIONS: &[(u32, u32)] = &[(0, 11)];
+
+fn find_header(dirs: &[PathBuf], candidates: &[&str]) -> PathBuf {
+    dirs.iter()
+        .flat_map(|dir| candidates.iter().map(move |candidate| dir.join(candidate)))
+        .find(|header| header.is_file())
+        .unwrap_or_else(|| panic!("could not find {} in {:?}", candidates[0], dirs))
+}
+
+/// Reads the value of `name` from a declaration like `enum { Major = 0 };`.
+fn read_enum_value(header: &Path, name: &str) -> u32 {
+    let source = fs::read_to_string(header)
+        .unwrap_or_else(|err| panic!("could not read {}: {}", header.display(), err));
+    source.match_indices(name)
+        .filter(|&(i, _)| !source[..i].ends_with(|c: char| c.is_alphanumeric() || c == '_'))
+        .filter_map(|(i, _)| source[i + name.len()..].trim_start().strip_prefix('='))
+        .filter_map(|value| {
+            let value = value.trim_start();
+            let end = value.find(|c: char| !c.is_ascii_digit()).unwrap_or(value.len());
+            value[..end].parse().ok()
+        })
+        .next()
+        .unwrap_or_else(|| panic!("could not find {} in {}", name, header.display()))
+}
+
+/// Checks the version of the Tracy client and makes it available as `CLIENT_VERSION` and
+/// `PROTOCOL_VERSION`.
+fn check_version(include_dirs: &[PathBuf]) {
+    let version_header = find_header(include_dirs, &[
+        "common/TracyVersion.hpp",
+        "server/TracyVersion.hpp",
+        "tracy/common/TracyVersion.hpp",
+    ]);
+    let protocol_header = find_header(include_dirs, &[
+        "common/TracyProtocol.hpp",
+        "tracy/common/TracyProtocol.hpp",
+    ]);
+    println!("cargo:rerun-if-changed={}", version_header.display());
+    println!("cargo:rerun-if-changed={}", protocol_header.display());
+
+    let major = read_enum_value(&version_header, "Major");
+    let minor = read_enum_value(&version_header, "Minor");
+    let patch = read_enum_value(&version_header, "Patch");
+    let protocol = read_enum_value(&protocol_header, "ProtocolVersion");
+
+    if !SUPPORTED_VERSIONS.contains(&(major, minor)) {
+        let supported: Vec<_> = SUPPORTED_VERSIONS.iter()
+            .map(|(major, minor)| format!("{}.{}.x", major, minor))
+            .collect();
+        panic!(
+            "Tracy {}.{}.{} (from {}) is not supported by tracy-rs; supported versions are {}",
+            major, minor, patch, version_header.display(), supported.join(", "),
+        );
+    }
+
+    let out_dir = PathBuf::from(env::var_os("OUT_DIR").unwrap());
+    fs::write(out_dir.join("version.rs"), format!(
+        "/// The version of the Tracy client this was built against.\n\
+         pub const CLIENT_VERSION: Version = Version {{ major: {}, minor: {}, patch: {} }};\n\
+         /// The network protocol version of the Tracy client; the viewer must use the same one.\n\
+         pub const PROTOCOL_VERSION: u32 = {};\n",
+        major, minor, patch, protocol,
+    )).unwrap();
+}
+
+#[cfg(feature = "bindgen")]
+fn generate_bindings(include_dirs: &[PathBuf]) {
+    let header = find_header(include_dirs, &["tracy/TracyC.h", "TracyC.h"]);
+    let bindings = bindgen::Builder::default()
+        .header(header.to_string_lossy())
+        .clang_args(include_dirs.iter().map(|dir| format!("-I{}", dir.display())))
+        // declare the optional parts of the API as well, like src/sys/bindings.rs
+        .clang_args(&["-DTRACY_ENABLE", "-DTRACY_FIBERS", "-DTRACY_MANUAL_LIFETIME"])
+        .allowlist_function("___tracy_.*")
+        .allowlist_type("___tracy_.*|__tracy_.*|TracyC.*|TracyPlotFormatEnum")
+        .generate()
+        .unwrap_or_else(|err| panic!("could not generate the Tracy bindings: {}", err));
+    let out_dir = PathBuf::from(env::var_os("OUT_DIR").unwrap());
+    bindings.write_to_file(out_dir.join("bindings.rs")).unwrap();
+}
+
+/// Generates the constants in `colors` from data/rgb.txt, which uses the format of X11's rgb.txt.
+fn generate_colors() {
+    let path = Path::new("data/rgb.txt");
+    println!("cargo:rerun-if-changed={}", path.display());
+    let data = fs::read_to_string(path)
+        .unwrap_or_else(|err| panic!("could not read {}: {}", path.display(), err));
+
+    let mut names = HashSet::new();
+    let mut colors = String::new();
+    for (line_number, line) in data.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('!') {
+            continue;
+        }
+
+        let mut fields = line.split_whitespace();
+        let mut rgb = [0u8; 3];
+        for component in rgb.iter_mut() {
+            *component = fields.next().and_then(|component| component.parse().ok())
+                .unwrap_or_else(|| panic!("{}:{}: expected red, green and blue values", path.display(), line_number + 1));
+        }
+        // "ghost white" becomes GhostWhite
+        let name: String = fields
+            .flat_map(|word| {
+                let mut chars = word.chars();
+                chars.next().map(|first| first.to_ascii_uppercase()).into_iter().chain(chars)
+            })
+            .collect();
+        if !name.starts_with(|c: char| c.is_ascii_alphabetic()) || !name.chars().all(|c| c.is_ascii_alphanumeric()) {
+            panic!("{}:{}: {:?} is not a valid color name", path.display(), line_number + 1, name);
+        }
+        // X11's rgb.txt lists most colors both with and without spaces
+        if !names.insert(name.clone()) {
+            continue;
+        }
+
+        writeln!(
+            colors, "pub const {:<21} Color = Color(0x{:02x}{:02x}{:02x});",
+            format!("{}:", name), rgb[0], rgb[1], rgb[2],
+        ).unwrap();
+    }
+
+    let out_dir = PathBuf::from(env::var_os("OUT_DIR").unwrap());
+    fs::write(out_dir.join("colors.rs"), colors).unwrap();
+}
+
 fn main() {
+    generate_colors();
+
     let mut cc_build = cc::Build::new();
     cc_build.use_plt(false)
             .flag_if_supported("-flto")
@@ -6,16 +222,31 @@ fn main() {
             .cpp(true)
             .flag_if_supported("-Wno-implicit-fallthrough")
             .flag_if_supported("-Wno-unused-function")
-            .flag_if_supported("-Wno-unused-parameter")
-            .include("./tracy");
+            .flag_if_supported("-Wno-unused-parameter");
 
-    if cfg!(feature = "dll") {
-        cc_build.file("./tracy/TracyClientDLL.cpp");
-    } else {
-        cc_build.file("./tracy/TracyClient.cpp");
-    }
+    let include_dirs = match find_client() {
+        Client::Source(root) => {
+            cc_build.include(&root);
+            if cfg!(feature = "dll") {
+                cc_build.file(root.join("TracyClientDLL.cpp"));
+            } else {
+                cc_build.file(root.join("TracyClient.cpp"));
+            }
+            println!("cargo:rerun-if-changed={}", root.display());
+            vec![root]
+        },
+        Client::Prebuilt(includes) => {
+            cc_build.includes(&includes);
+            includes
+        },
+    };
+    check_version(&include_dirs);
+    #[cfg(feature = "bindgen")]
+    generate_bindings(&include_dirs);
+    cc_build.file("./src/shim.cpp");
 
-    if cfg!(feature = "enable") {
+    // the other backends don't use the client, so it shouldn't be started either
+    if cfg!(feature = "enable") && !cfg!(any(feature = "chrome_trace", feature = "folded_stacks")) {
         cc_build.define("TRACY_ENABLE", None);
     }
 
@@ -27,5 +258,77 @@ fn main() {
         cc_build.define("TRACY_ON_DEMAND", None);
     }
 
+    let options = [
+        (cfg!(feature = "no_broadcast"), "TRACY_NO_BROADCAST"),
+        (cfg!(feature = "only_localhost"), "TRACY_ONLY_LOCALHOST"),
+        (cfg!(feature = "only_ipv4"), "TRACY_ONLY_IPV4"),
+        (cfg!(feature = "no_sampling"), "TRACY_NO_SAMPLING"),
+        (cfg!(feature = "no_context_switch"), "TRACY_NO_CONTEXT_SWITCH"),
+        (cfg!(feature = "no_system_tracing"), "TRACY_NO_SYSTEM_TRACING"),
+        (cfg!(feature = "no_callstack"), "TRACY_NO_CALLSTACK"),
+        (cfg!(feature = "no_code_transfer"), "TRACY_NO_CODE_TRANSFER"),
+        (cfg!(feature = "no_frame_image"), "TRACY_NO_FRAME_IMAGE"),
+        (cfg!(feature = "no_vsync_capture"), "TRACY_NO_VSYNC_CAPTURE"),
+        (cfg!(feature = "timer_fallback"), "TRACY_TIMER_FALLBACK"),
+        // src/demangle.rs provides ___tracy_demangle
+        (cfg!(feature = "demangle"), "TRACY_DEMANGLE"),
+    ];
+    for &(enabled, define) in options.iter() {
+        if enabled {
+            cc_build.define(define, None);
+        }
+    }
+
+    // the default callstack depth; Tracy captures at most 62 frames
+    println!("cargo:rerun-if-env-changed=TRACY_CALLSTACK");
+    let callstack_depth = env::var("TRACY_CALLSTACK").ok()
+        .map(|depth| match depth.parse() {
+            Ok(depth @ 1..=62) => depth,
+            _ => panic!("TRACY_CALLSTACK must be a callstack depth between 1 and 62, got {:?}", depth),
+        })
+        .or(cfg!(feature = "callstack").then_some(62u32));
+    if let Some(depth) = callstack_depth {
+        if cfg!(feature = "no_callstack") {
+            panic!("a default callstack depth cannot be used with the \"no_callstack\" feature");
+        }
+        cc_build.define("TRACY_CALLSTACK", Some(depth.to_string().as_str()));
+        println!("cargo:rustc-env=TRACY_RS_CALLSTACK_DEPTH={}", depth);
+    }
+
+    for &var in ["TRACY_DATA_PORT", "TRACY_BROADCAST_PORT"].iter() {
+        println!("cargo:rerun-if-env-changed={}", var);
+        if let Ok(port) = env::var(var) {
+            let port: u16 = port.parse()
+                .unwrap_or_else(|_| panic!("{} must be a port number, got {:?}", var, port));
+            cc_build.define(var, Some(port.to_string().as_str()));
+        }
+    }
+
+    // for report_build_info!
+    let mut features: Vec<_> = env::vars()
+        .filter_map(|(var, _)| var.strip_prefix("CARGO_FEATURE_").map(str::to_lowercase))
+        .collect();
+    features.sort();
+    println!("cargo:rustc-env=TRACY_RS_FEATURES={}", features.join(","));
+    println!("cargo:rustc-env=TRACY_RS_TARGET={}", env::var("TARGET").unwrap());
+
+    let target_os = env::var("CARGO_CFG_TARGET_OS").unwrap();
+    if cfg!(feature = "test_backend") {
+        // src/demangle.rs calls __cxa_demangle, which otherwise comes with the client's C++ runtime
+        if cfg!(feature = "demangle") && env::var("CARGO_CFG_TARGET_FAMILY").unwrap() == "unix" {
+            let runtime = match target_os.as_str() {
+                "macos" | "ios" | "freebsd" | "openbsd" => "c++",
+                _ => "stdc++",
+            };
+            println!("cargo:rustc-link-lib={}", runtime);
+        }
+        return;
+    }
+    println!("cargo:rerun-if-changed=src/shim.cpp");
+    // for ___tracy_rs_listen_port
+    if target_os == "windows" {
+        println!("cargo:rustc-link-lib=iphlpapi");
+    }
+
     cc_build.compile("libtracy.a");
 }
@@ -0,0 +1,353 @@
+//! The backends that zones, frame marks, messages, plots and the other events are sent to.
+//!
+//! The backend is selected at compile time by the features of this crate, so the instrumentation
+//! in the profiled crates stays the same: `Tracy` by default, `chrome_trace::ChromeTrace` with the
+//! "chrome_trace" feature, `folded_stacks::FoldedStacks` with the "folded_stacks" feature, and
+//! `Null` without any of them. All calls go through `Active`, which is statically dispatched, so
+//! there is no overhead over calling the backend directly.
+
+use crate::{sys, Color, SourceLocationData};
+use libc::{c_char, c_int, c_void, size_t};
+use std::{borrow::Cow, cmp, ffi::CStr, mem, ptr, thread, time::{Duration, Instant}};
+use std::sync::atomic::{AtomicU16, Ordering};
+
+/// Looks up the contents of a source file the viewer asks for, see `crate::source`.
+pub type SourceCallback = dyn Fn(&str) -> Option<Cow<'static, [u8]>> + Send + Sync;
+
+/// Receives the events of the instrumentation.
+///
+/// `callstack_depth` is the number of frames to capture, or 0 for no callstack.
+pub trait Backend {
+    /// What the backend keeps for an open zone.
+    type Zone: Copy;
+
+    fn zone_begin(loc: &SourceLocationData<'_>, callstack_depth: c_int, active: bool) -> Self::Zone;
+
+    fn zone_end(zone: Self::Zone);
+
+    fn zone_is_active(zone: Self::Zone) -> bool;
+
+    fn zone_text(zone: Self::Zone, text: &str);
+
+    fn zone_color(zone: Self::Zone, color: Color);
+
+    fn zone_value(zone: Self::Zone, value: u64);
+
+    /// `name` is `None` for the main frame.
+    fn frame_mark(name: Option<&'static CStr>);
+
+    fn frame_mark_start(name: &'static CStr);
+
+    fn frame_mark_end(name: &'static CStr);
+
+    fn message(text: &str, color: Option<Color>, callstack_depth: c_int);
+
+    fn app_info(info: &str);
+
+    fn plot(name: &'static CStr, value: f64);
+
+    /// # Safety
+    ///
+    /// `image` must point to `width` * `height` RGBA pixels.
+    unsafe fn frame_image(image: *const c_void, width: u16, height: u16, offset: u8, flip: bool);
+
+    fn memory_alloc(ptr: *const u8, size: usize, callstack_depth: c_int);
+
+    fn memory_free(ptr: *const u8, callstack_depth: c_int);
+
+    /// Has `changed` called with the index and the new value of parameters changed by a consumer.
+    fn parameter_register(changed: fn(index: u32, value: i32));
+
+    fn parameter_setup(index: u32, name: &'static CStr, is_bool: bool, value: i32);
+
+    /// Has `callback` look up the source files a consumer asks for.
+    fn source_callback_register(callback: &'static SourceCallback);
+
+    /// Sends or writes the events recorded so far and stops recording, waiting at most `timeout`.
+    ///
+    /// Returns whether all events were sent or written.
+    fn flush(timeout: Duration) -> bool;
+
+    /// Whether a consumer of the events is attached, e.g. a Tracy viewer.
+    fn is_connected() -> bool;
+
+    /// The port consumers connect to, if the backend is listening on one.
+    fn listen_port() -> Option<u16>;
+}
+
+/// The backend selected by the features of this crate.
+///
+/// "test_backend" records what is sent to Tracy, so it takes precedence, followed by
+/// "chrome_trace", "folded_stacks" and "enable".
+#[cfg(feature = "test_backend")]
+pub type Active = Tracy;
+#[cfg(all(feature = "chrome_trace", not(feature = "test_backend")))]
+pub type Active = crate::chrome_trace::ChromeTrace;
+#[cfg(all(feature = "folded_stacks", not(any(feature = "test_backend", feature = "chrome_trace"))))]
+pub type Active = crate::folded_stacks::FoldedStacks;
+#[cfg(all(feature = "enable", not(any(feature = "test_backend", feature = "chrome_trace", feature = "folded_stacks"))))]
+pub type Active = Tracy;
+#[cfg(not(any(feature = "enable", feature = "test_backend", feature = "chrome_trace", feature = "folded_stacks")))]
+pub type Active = Null;
+
+/// Sends the events to the Tracy client.
+pub enum Tracy {}
+
+impl Backend for Tracy {
+    type Zone = sys::zone_context;
+
+    #[inline]
+    fn zone_begin(loc: &SourceLocationData<'_>, callstack_depth: c_int, active: bool) -> Self::Zone {
+        let active = if active { 1 } else { 0 };
+        unsafe {
+            if callstack_depth == 0 {
+                sys::___tracy_emit_zone_begin(&loc.data as *const _, active)
+            } else {
+                sys::___tracy_emit_zone_begin_callstack(&loc.data as *const _, callstack_depth, active)
+            }
+        }
+    }
+
+    #[inline]
+    fn zone_end(zone: Self::Zone) {
+        unsafe{ sys::___tracy_emit_zone_end(zone) };
+    }
+
+    #[inline]
+    fn zone_is_active(zone: Self::Zone) -> bool {
+        zone.active != 0
+    }
+
+    #[inline]
+    fn zone_text(zone: Self::Zone, text: &str) {
+        unsafe{ sys::___tracy_emit_zone_text(zone, text.as_ptr() as *const _, text.len()) };
+    }
+
+    #[inline]
+    fn zone_color(zone: Self::Zone, color: Color) {
+        unsafe{ sys::___tracy_emit_zone_color(zone, color.0) };
+    }
+
+    #[inline]
+    fn zone_value(zone: Self::Zone, value: u64) {
+        unsafe{ sys::___tracy_emit_zone_value(zone, value) };
+    }
+
+    #[inline]
+    fn frame_mark(name: Option<&'static CStr>) {
+        unsafe{ sys::___tracy_emit_frame_mark(name.map_or(std::ptr::null(), CStr::as_ptr)) };
+    }
+
+    #[inline]
+    fn frame_mark_start(name: &'static CStr) {
+        unsafe{ sys::___tracy_emit_frame_mark_start(name.as_ptr()) };
+    }
+
+    #[inline]
+    fn frame_mark_end(name: &'static CStr) {
+        unsafe{ sys::___tracy_emit_frame_mark_end(name.as_ptr()) };
+    }
+
+    #[inline]
+    fn message(text: &str, color: Option<Color>, callstack_depth: c_int) {
+        match color {
+            Some(color) => unsafe{ sys::___tracy_emit_messageC(text.as_ptr() as *const _, text.len(), color.0, callstack_depth) },
+            None => unsafe{ sys::___tracy_emit_message(text.as_ptr() as *const _, text.len(), callstack_depth) },
+        }
+    }
+
+    #[inline]
+    fn app_info(info: &str) {
+        unsafe{ sys::___tracy_emit_message_appinfo(info.as_ptr() as *const _, info.len()) };
+    }
+
+    #[inline]
+    fn plot(name: &'static CStr, value: f64) {
+        unsafe{ sys::___tracy_emit_plot(name.as_ptr(), value) };
+    }
+
+    #[inline]
+    unsafe fn frame_image(image: *const c_void, width: u16, height: u16, offset: u8, flip: bool) {
+        sys::___tracy_emit_frame_image(image, width, height, offset, if flip { 1 } else { 0 });
+    }
+
+    #[inline]
+    fn memory_alloc(ptr: *const u8, size: usize, callstack_depth: c_int) {
+        unsafe {
+            if callstack_depth > 0 {
+                sys::___tracy_emit_memory_alloc_callstack(ptr as *const _, size, callstack_depth, 1);
+            } else {
+                sys::___tracy_emit_memory_alloc(ptr as *const _, size, 1);
+            }
+        }
+    }
+
+    #[inline]
+    fn memory_free(ptr: *const u8, callstack_depth: c_int) {
+        unsafe {
+            if callstack_depth > 0 {
+                sys::___tracy_emit_memory_free_callstack(ptr as *const _, callstack_depth, 1);
+            } else {
+                sys::___tracy_emit_memory_free(ptr as *const _, 1);
+            }
+        }
+    }
+
+    fn parameter_register(changed: fn(index: u32, value: i32)) {
+        unsafe extern "C" fn parameter_changed(data: *mut c_void, index: u32, value: i32) {
+            let changed: fn(u32, i32) = mem::transmute(data);
+            changed(index, value);
+        }
+
+        unsafe{ sys::___tracy_rs_parameter_register(Some(parameter_changed), changed as *mut c_void) };
+    }
+
+    fn parameter_setup(index: u32, name: &'static CStr, is_bool: bool, value: i32) {
+        unsafe{ sys::___tracy_rs_parameter_setup(index, name.as_ptr(), is_bool as _, value) };
+    }
+
+    fn source_callback_register(callback: &'static SourceCallback) {
+        unsafe extern "C" fn source_callback(data: *mut c_void, filename: *const c_char, size: *mut size_t) -> *mut c_char {
+            let callback = *(data as *const &SourceCallback);
+            let contents = match CStr::from_ptr(filename).to_str().ok().and_then(callback) {
+                Some(contents) => contents,
+                None => return ptr::null_mut(),
+            };
+            //Tracy frees the contents with its own allocator
+            let buffer = sys::___tracy_rs_malloc(contents.len()) as *mut u8;
+            if buffer.is_null() {
+                return ptr::null_mut();
+            }
+            ptr::copy_nonoverlapping(contents.as_ptr(), buffer, contents.len());
+            *size = contents.len();
+            buffer as *mut c_char
+        }
+
+        //the callback is a fat pointer, so pass a pointer to it instead
+        let callback = Box::into_raw(Box::new(callback));
+        unsafe{ sys::___tracy_rs_source_callback_register(Some(source_callback), callback as *mut c_void) };
+    }
+
+    /// Returns immediately if no viewer is connected.
+    fn flush(timeout: Duration) -> bool {
+        if !Self::is_connected() {
+            return false;
+        }
+        unsafe{ sys::___tracy_rs_request_shutdown() };
+        let start = Instant::now();
+        while unsafe{ sys::___tracy_rs_shutdown_finished() } == 0 {
+            let elapsed = start.elapsed();
+            if elapsed >= timeout {
+                return false;
+            }
+            thread::sleep(cmp::min(timeout - elapsed, crate::POLL_INTERVAL));
+        }
+        true
+    }
+
+    #[inline]
+    fn is_connected() -> bool {
+        unsafe{ sys::___tracy_connected() != 0 }
+    }
+
+    fn listen_port() -> Option<u16> {
+        // Tracy doesn't move once it listens, so only search for the port until it is found
+        static PORT: AtomicU16 = AtomicU16::new(0);
+        let port = match PORT.load(Ordering::Relaxed) {
+            0 => {
+                let port = unsafe{ sys::___tracy_rs_listen_port() };
+                PORT.store(port, Ordering::Relaxed);
+                port
+            }
+            port => port,
+        };
+        match port {
+            0 => None,
+            port => Some(port),
+        }
+    }
+}
+
+/// Discards all events; zones are never active.
+pub enum Null {}
+
+/// A zone of `Null`.
+#[derive(Clone, Copy, Debug)]
+pub struct NullZone;
+
+impl Backend for Null {
+    type Zone = NullZone;
+
+    #[inline]
+    fn zone_begin(_loc: &SourceLocationData<'_>, _callstack_depth: c_int, _active: bool) -> Self::Zone {
+        NullZone
+    }
+
+    #[inline]
+    fn zone_end(_zone: Self::Zone) {}
+
+    #[inline]
+    fn zone_is_active(_zone: Self::Zone) -> bool {
+        false
+    }
+
+    #[inline]
+    fn zone_text(_zone: Self::Zone, _text: &str) {}
+
+    #[inline]
+    fn zone_color(_zone: Self::Zone, _color: Color) {}
+
+    #[inline]
+    fn zone_value(_zone: Self::Zone, _value: u64) {}
+
+    #[inline]
+    fn frame_mark(_name: Option<&'static CStr>) {}
+
+    #[inline]
+    fn frame_mark_start(_name: &'static CStr) {}
+
+    #[inline]
+    fn frame_mark_end(_name: &'static CStr) {}
+
+    #[inline]
+    fn message(_text: &str, _color: Option<Color>, _callstack_depth: c_int) {}
+
+    #[inline]
+    fn app_info(_info: &str) {}
+
+    #[inline]
+    fn plot(_name: &'static CStr, _value: f64) {}
+
+    #[inline]
+    unsafe fn frame_image(_image: *const c_void, _width: u16, _height: u16, _offset: u8, _flip: bool) {}
+
+    #[inline]
+    fn memory_alloc(_ptr: *const u8, _size: usize, _callstack_depth: c_int) {}
+
+    #[inline]
+    fn memory_free(_ptr: *const u8, _callstack_depth: c_int) {}
+
+    #[inline]
+    fn parameter_register(_changed: fn(index: u32, value: i32)) {}
+
+    #[inline]
+    fn parameter_setup(_index: u32, _name: &'static CStr, _is_bool: bool, _value: i32) {}
+
+    #[inline]
+    fn source_callback_register(_callback: &'static SourceCallback) {}
+
+    #[inline]
+    fn flush(_timeout: Duration) -> bool {
+        false
+    }
+
+    #[inline]
+    fn is_connected() -> bool {
+        false
+    }
+
+    #[inline]
+    fn listen_port() -> Option<u16> {
+        None
+    }
+}
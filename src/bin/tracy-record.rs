@@ -0,0 +1,287 @@
+//! Connects to a Tracy client and records what it sends to a file, without the viewer.
+//!
+//! The recording can be read with `tracy_rs::file`; it is not a .tracy file the viewer can open.
+
+use std::{
+    collections::{HashSet, VecDeque},
+    env,
+    fs::OpenOptions,
+    io::{self, BufWriter, ErrorKind, Read, Write},
+    net::TcpStream,
+    process,
+    sync::atomic::{AtomicBool, Ordering},
+    time::{Duration, Instant},
+};
+use tracy_rs::protocol::{self, Decompressor, Error, Events, QueueType, ServerQuery, Welcome};
+
+const USAGE: &str = "\
+usage: tracy-record -o OUTPUT [-a ADDRESS] [-p PORT] [-f] [-s SECONDS]
+
+  -o OUTPUT   the recording to write
+  -a ADDRESS  the address of the client, 127.0.0.1 by default
+  -p PORT     the port of the client, 8086 by default
+  -f          overwrite OUTPUT if it exists
+  -s SECONDS  disconnect after SECONDS; otherwise until the client exits or on Ctrl-C
+";
+
+/// How often reading from the client checks whether to stop.
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// The queries sent before waiting for their replies, so the client's receive buffer doesn't fill
+/// up while it is busy sending.
+const MAX_PENDING_QUERIES: usize = 1024;
+
+/// How long to wait for replies to the outstanding queries before ending the recording anyway.
+const STALL_TIMEOUT: Duration = Duration::from_secs(5);
+
+static INTERRUPTED: AtomicBool = AtomicBool::new(false);
+
+extern "C" fn interrupted(_signal: libc::c_int) {
+    INTERRUPTED.store(true, Ordering::Relaxed);
+}
+
+struct Options {
+    output: String,
+    address: String,
+    port: u16,
+    force: bool,
+    seconds: Option<f64>,
+}
+
+fn parse_args() -> Result<Options, String> {
+    let mut output = None;
+    let mut options = Options {
+        output: String::new(),
+        address: "127.0.0.1".to_string(),
+        port: 8086,
+        force: false,
+        seconds: None,
+    };
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().ok_or_else(|| format!("{} requires a value", arg));
+        match arg.as_str() {
+            "-o" => output = Some(value()?),
+            "-a" => options.address = value()?,
+            "-p" => options.port = value()?.parse().map_err(|_| "-p requires a port number".to_string())?,
+            "-f" => options.force = true,
+            "-s" => options.seconds = Some(value()?.parse().map_err(|_| "-s requires a number of seconds".to_string())?),
+            "-h" | "--help" => {
+                print!("{}", USAGE);
+                process::exit(0);
+            },
+            _ => return Err(format!("unexpected argument {}", arg)),
+        }
+    }
+    options.output = output.ok_or("-o is required")?;
+    Ok(options)
+}
+
+/// Reads from the client and sends it queries, checking `stop` while waiting for data.
+struct Connection<'a> {
+    stream: TcpStream,
+    stop: &'a dyn Fn() -> bool,
+    queried: HashSet<(ServerQuery, u64)>,
+    queries: VecDeque<(ServerQuery, u64)>,
+    /// The queries sent since they were last written to the recording.
+    sent: Vec<(ServerQuery, u64)>,
+    pending: usize,
+    /// The query that ends the recording once the other queries are answered, and when the last
+    /// query was answered or the recording was asked to end.
+    ending: Option<(ServerQuery, Instant)>,
+    ended: bool,
+}
+
+impl Connection<'_> {
+    /// Ends the recording once the queries sent so far are answered.
+    ///
+    /// `Terminate` takes precedence over `Disconnect`, since the client waits for it before exiting.
+    fn end(&mut self, query: ServerQuery) {
+        match &mut self.ending {
+            Some((ending, _)) if query == ServerQuery::Terminate => *ending = query,
+            Some(_) => (),
+            None => self.ending = Some((query, Instant::now())),
+        }
+    }
+
+    /// Checks whether to stop and sends the queued queries, then the query that ends the recording
+    /// once all of them are answered.
+    fn poll(&mut self) -> Result<(), Error> {
+        if self.ended {
+            return Ok(());
+        }
+        if self.ending.is_none() && (self.stop)() {
+            //the client sends what it has left and closes the connection
+            eprintln!("disconnecting");
+            self.end(ServerQuery::Disconnect);
+        }
+
+        while self.pending < MAX_PENDING_QUERIES {
+            let (query, ptr) = match self.queries.pop_front() {
+                Some(query) => query,
+                None => break,
+            };
+            self.stream.write_all(&query.packet(ptr, 0))?;
+            self.sent.push((query, ptr));
+            self.pending += 1;
+        }
+
+        if let Some((query, since)) = self.ending {
+            let answered = self.queries.is_empty() && self.pending == 0;
+            if answered || since.elapsed() >= STALL_TIMEOUT {
+                if !answered {
+                    eprintln!("{} queries were not answered", self.queries.len() + self.pending);
+                }
+                self.stream.write_all(&query.packet(0, 0))?;
+                self.ended = true;
+            }
+        }
+        Ok(())
+    }
+
+    fn answered(&mut self) {
+        self.pending = self.pending.saturating_sub(1);
+        if let Some((_, since)) = &mut self.ending {
+            *since = Instant::now();
+        }
+    }
+
+    /// Fills `buf`, or returns `false` if the client closed the connection before sending anything.
+    fn read_exact(&mut self, buf: &mut [u8]) -> Result<bool, Error> {
+        let mut filled = 0;
+        while filled < buf.len() {
+            match self.stream.read(&mut buf[filled..]) {
+                Ok(0) if filled == 0 => return Ok(false),
+                Ok(0) => return Err(io::Error::from(ErrorKind::UnexpectedEof).into()),
+                Ok(n) => filled += n,
+                Err(err) if matches!(err.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut | ErrorKind::Interrupted) => {
+                    self.poll()?;
+                },
+                Err(err) => return Err(err.into()),
+            }
+        }
+        Ok(true)
+    }
+}
+
+fn record(options: &Options, output: &mut impl Write) -> Result<(), Error> {
+    let stream = TcpStream::connect((options.address.as_str(), options.port))?;
+    stream.set_nodelay(true)?;
+    stream.set_read_timeout(Some(POLL_INTERVAL))?;
+
+    let start = Instant::now();
+    let seconds = options.seconds;
+    let stop = move || INTERRUPTED.load(Ordering::Relaxed) || seconds.is_some_and(|s| start.elapsed().as_secs_f64() >= s);
+    let mut connection = Connection {
+        stream,
+        stop: &stop,
+        queried: HashSet::new(),
+        queries: VecDeque::new(),
+        sent: Vec::new(),
+        pending: 0,
+        ending: None,
+        ended: false,
+    };
+
+    let mut handshake = protocol::HANDSHAKE_SHIBBOLETH.to_vec();
+    handshake.extend_from_slice(&tracy_rs::PROTOCOL_VERSION.to_le_bytes());
+    connection.stream.write_all(&handshake)?;
+
+    let mut status = [0];
+    if !connection.read_exact(&mut status)? {
+        return Err(Error::Format("the client closed the connection during the handshake".to_string()));
+    }
+    if status[0] != protocol::HANDSHAKE_WELCOME {
+        return Err(Error::Handshake(status[0]));
+    }
+    let mut welcome = [0; protocol::WELCOME_SIZE];
+    connection.read_exact(&mut welcome)?;
+    let parsed = Welcome::parse(&welcome);
+    eprintln!("connected to {} (pid {})", parsed.program_name, parsed.pid);
+
+    output.write_all(protocol::RECORDING_MAGIC)?;
+    output.write_all(&protocol::RECORDING_VERSION.to_le_bytes())?;
+    output.write_all(&tracy_rs::PROTOCOL_VERSION.to_le_bytes())?;
+    output.write_all(&welcome)?;
+    if parsed.flags & protocol::WELCOME_FLAG_ON_DEMAND != 0 {
+        let mut on_demand = [0; protocol::ON_DEMAND_PAYLOAD_SIZE];
+        connection.read_exact(&mut on_demand)?;
+        output.write_all(&[1])?;
+        output.write_all(&on_demand)?;
+    } else {
+        output.write_all(&[0])?;
+    }
+
+    let mut decompressor = Decompressor::new();
+    let mut frame = Vec::new();
+    let mut received = 0;
+    loop {
+        let mut size = [0; 4];
+        if !connection.read_exact(&mut size)? {
+            break;
+        }
+        frame.resize(u32::from_le_bytes(size) as usize, 0);
+        connection.read_exact(&mut frame)?;
+        //the replies to these are in this frame or later ones
+        for (query, ptr) in connection.sent.drain(..) {
+            output.write_all(&[protocol::RECORD_QUERY, query as u8])?;
+            output.write_all(&ptr.to_le_bytes())?;
+        }
+        output.write_all(&[protocol::RECORD_FRAME])?;
+        output.write_all(&size)?;
+        output.write_all(&frame)?;
+        received += frame.len();
+
+        for event in Events::new(decompressor.decompress(&frame)?) {
+            let event = match event {
+                Ok(event) => event,
+                Err(err) => {
+                    //the next frame starts with a new event, so the recording can go on
+                    eprintln!("skipping the rest of a frame: {}", err);
+                    break;
+                },
+            };
+            if protocol::answers_query(&event) {
+                connection.answered();
+            }
+            if event.ty == QueueType::Terminate {
+                connection.end(ServerQuery::Terminate);
+            }
+            let queried = &mut connection.queried;
+            let queries = protocol::queries_for(&event, |query, ptr| !queried.insert((query, ptr)));
+            connection.queries.extend(queries);
+        }
+        connection.poll()?;
+    }
+    eprintln!("received {} KiB in {:.1} s", received / 1024, start.elapsed().as_secs_f64());
+    Ok(())
+}
+
+fn main() {
+    let options = parse_args().unwrap_or_else(|err| {
+        eprint!("{}\n\n{}", err, USAGE);
+        process::exit(2);
+    });
+
+    let file = OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .create_new(!options.force)
+        .open(&options.output);
+    let mut output = match file {
+        Ok(file) => BufWriter::new(file),
+        Err(err) => {
+            eprintln!("failed to create {}: {}", options.output, err);
+            process::exit(1);
+        },
+    };
+
+    unsafe{ libc::signal(libc::SIGINT, interrupted as extern "C" fn(libc::c_int) as libc::sighandler_t) };
+
+    let result = record(&options, &mut output).and_then(|()| output.flush().map_err(Error::from));
+    if let Err(err) = result {
+        eprintln!("recording failed: {}", err);
+        process::exit(1);
+    }
+}
@@ -0,0 +1,437 @@
+//! Prints statistics of the zones in a recording written by tracy-record, or compares them with
+//! the statistics of a baseline recording. The .tracy files saved by the viewer can't be read.
+//!
+//! When comparing, the exit status is 1 if a zone regressed by more than the threshold, so it can
+//! gate performance changes in CI, and 2 if the recordings couldn't be read or lack a compared zone.
+
+use std::{collections::HashMap, env, fmt, process};
+use tracy_rs::file::Recording;
+
+const USAGE: &str = "\
+usage: tracy-stats [-n ROWS] RECORDING
+       tracy-stats -b BASELINE [-z ZONE]... [-m METRIC] [-t PERCENT] RECORDING
+
+  -n ROWS      print only the ROWS zones with the most total time
+  -b BASELINE  compare RECORDING with the recording BASELINE
+  -z ZONE      the zone to compare, by name; may be repeated; all zones in both recordings by default
+  -m METRIC    what to compare: total, mean, median, p90, p99, max or self; median by default
+  -t PERCENT   the increase of METRIC that is a regression, 10 by default
+
+self is the total time spent in the zones excluding their child zones.
+RECORDING and BASELINE are written by tracy-record; .tracy files can't be read.
+Exits with 1 if a compared zone regressed by more than PERCENT, or 2 on errors.
+";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Metric {
+    Total,
+    Mean,
+    Median,
+    P90,
+    P99,
+    Max,
+    SelfTime,
+}
+
+impl Metric {
+    fn parse(name: &str) -> Option<Self> {
+        Some(match name {
+            "total" => Metric::Total,
+            "mean" => Metric::Mean,
+            "median" => Metric::Median,
+            "p90" => Metric::P90,
+            "p99" => Metric::P99,
+            "max" => Metric::Max,
+            "self" => Metric::SelfTime,
+            _ => return None,
+        })
+    }
+}
+
+struct Options {
+    recording: String,
+    rows: Option<usize>,
+    baseline: Option<String>,
+    zones: Vec<String>,
+    metric: Metric,
+    threshold: f64,
+}
+
+fn parse_args() -> Result<Options, String> {
+    let mut recording = None;
+    let mut options = Options {
+        recording: String::new(),
+        rows: None,
+        baseline: None,
+        zones: Vec::new(),
+        metric: Metric::Median,
+        threshold: 10.0,
+    };
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().ok_or_else(|| format!("{} requires a value", arg));
+        match arg.as_str() {
+            "-n" => options.rows = Some(value()?.parse().map_err(|_| "-n requires a number of rows".to_string())?),
+            "-b" => options.baseline = Some(value()?),
+            "-z" => options.zones.push(value()?),
+            "-m" => options.metric = Metric::parse(&value()?).ok_or("-m requires one of total, mean, median, p90, p99, max or self")?,
+            "-t" => options.threshold = value()?.parse().map_err(|_| "-t requires a percentage".to_string())?,
+            "-h" | "--help" => {
+                print!("{}", USAGE);
+                process::exit(0);
+            },
+            _ if arg.starts_with('-') => return Err(format!("unexpected argument {}", arg)),
+            _ if recording.is_none() => recording = Some(arg),
+            _ => return Err(format!("unexpected argument {}", arg)),
+        }
+    }
+    options.recording = recording.ok_or("a recording is required")?;
+    if options.baseline.is_none() && !options.zones.is_empty() {
+        return Err("-z requires -b".to_string());
+    }
+    Ok(options)
+}
+
+/// The statistics of the zones with the same name, in nanoseconds.
+#[derive(Clone, Debug)]
+struct Stats {
+    count: usize,
+    total: i64,
+    mean: i64,
+    median: i64,
+    p90: i64,
+    p99: i64,
+    max: i64,
+    self_time: i64,
+}
+
+impl Stats {
+    fn new(mut durations: Vec<i64>, self_time: i64) -> Self {
+        durations.sort_unstable();
+        let total = durations.iter().sum();
+        //the nearest rank
+        let percentile = |p: f64| durations[((p * durations.len() as f64).ceil() as usize).max(1) - 1];
+        Self {
+            count: durations.len(),
+            total,
+            mean: total / durations.len() as i64,
+            median: percentile(0.5),
+            p90: percentile(0.9),
+            p99: percentile(0.99),
+            max: durations[durations.len() - 1],
+            self_time,
+        }
+    }
+
+    fn get(&self, metric: Metric) -> i64 {
+        match metric {
+            Metric::Total => self.total,
+            Metric::Mean => self.mean,
+            Metric::Median => self.median,
+            Metric::P90 => self.p90,
+            Metric::P99 => self.p99,
+            Metric::Max => self.max,
+            Metric::SelfTime => self.self_time,
+        }
+    }
+}
+
+/// The statistics of the zones that ended, by name.
+fn zone_stats(recording: &Recording) -> HashMap<String, Stats> {
+    let mut zones: HashMap<&str, (Vec<i64>, i64)> = HashMap::new();
+    for thread in &recording.threads {
+        let mut self_times: Vec<_> = thread.zones.iter().map(|zone| zone.duration()).collect();
+        for zone in &thread.zones {
+            if let (Some(parent), Some(duration)) = (zone.parent, zone.duration()) {
+                if let Some(self_time) = &mut self_times[parent] {
+                    *self_time -= duration;
+                }
+            }
+        }
+        for (zone, self_time) in thread.zones.iter().zip(self_times) {
+            if let (Some(duration), Some(self_time)) = (zone.duration(), self_time) {
+                let name = zone.name.as_deref().unwrap_or_else(|| recording.source_locations[zone.location].name());
+                let (durations, total_self_time) = zones.entry(name).or_default();
+                durations.push(duration);
+                *total_self_time += self_time;
+            }
+        }
+    }
+    zones.into_iter()
+        .map(|(name, (durations, self_time))| (name.to_string(), Stats::new(durations, self_time)))
+        .collect()
+}
+
+struct Time(i64);
+
+impl fmt::Display for Time {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let ns = self.0 as f64;
+        let time = match self.0.abs() {
+            t if t < 1_000 => format!("{} ns", self.0),
+            t if t < 1_000_000 => format!("{:.2} us", ns / 1e3),
+            t if t < 1_000_000_000 => format!("{:.2} ms", ns / 1e6),
+            _ => format!("{:.2} s", ns / 1e9),
+        };
+        f.pad(&time)
+    }
+}
+
+fn open(path: &str) -> HashMap<String, Stats> {
+    match Recording::open(path) {
+        Ok(recording) => {
+            if recording.skipped_frames != 0 {
+                eprintln!("{}: skipped {} frames that couldn't be read", path, recording.skipped_frames);
+            }
+            zone_stats(&recording)
+        },
+        Err(err) => {
+            eprintln!("failed to read {}: {}", path, err);
+            process::exit(2);
+        },
+    }
+}
+
+fn print_stats(stats: &HashMap<String, Stats>, rows: Option<usize>) {
+    let mut stats: Vec<_> = stats.iter().collect();
+    stats.sort_by(|(a_name, a), (b_name, b)| b.total.cmp(&a.total).then_with(|| a_name.cmp(b_name)));
+    stats.truncate(rows.unwrap_or(usize::MAX));
+
+    let width = stats.iter().map(|(name, _)| name.chars().count()).max().unwrap_or(0).max(4);
+    println!(
+        "{:<width$} {:>8} {:>10} {:>10} {:>10} {:>10} {:>10} {:>10} {:>10}",
+        "zone", "count", "total", "mean", "median", "p90", "p99", "max", "self", width = width,
+    );
+    for (name, stats) in stats {
+        println!(
+            "{:<width$} {:>8} {:>10} {:>10} {:>10} {:>10} {:>10} {:>10} {:>10}",
+            name, stats.count, Time(stats.total), Time(stats.mean), Time(stats.median), Time(stats.p90),
+            Time(stats.p99), Time(stats.max), Time(stats.self_time), width = width,
+        );
+    }
+}
+
+/// A zone compared between the baseline and the current recording.
+#[derive(Clone, Debug, PartialEq)]
+struct Comparison<'a> {
+    zone: &'a str,
+    baseline: i64,
+    current: i64,
+    /// In percent.
+    change: f64,
+    regression: bool,
+}
+
+/// Compares the zones in `options.zones`, or those in both recordings if there are none, sorted by
+/// name.
+///
+/// Fails if a zone is missing from either recording, since it must not pass the check.
+fn compare<'a>(
+    options: &'a Options,
+    baseline: &'a HashMap<String, Stats>,
+    current: &'a HashMap<String, Stats>,
+) -> Result<Vec<Comparison<'a>>, String> {
+    let mut names: Vec<_> = if options.zones.is_empty() {
+        baseline.keys().filter(|name| current.contains_key(*name)).collect()
+    } else {
+        options.zones.iter().collect()
+    };
+    names.sort();
+    names.dedup();
+
+    names.into_iter()
+        .map(|name| {
+            let (baseline, current) = match (baseline.get(name), current.get(name)) {
+                (Some(baseline), Some(current)) => (baseline.get(options.metric), current.get(options.metric)),
+                (None, _) => return Err(format!("{} has no zone {}", options.baseline.as_deref().unwrap_or_default(), name)),
+                (_, None) => return Err(format!("{} has no zone {}", options.recording, name)),
+            };
+            let change = if baseline == 0 {
+                if current == 0 { 0.0 } else { f64::INFINITY }
+            } else {
+                (current - baseline) as f64 / baseline as f64 * 100.0
+            };
+            Ok(Comparison { zone: name, baseline, current, change, regression: change > options.threshold })
+        })
+        .collect()
+}
+
+fn print_comparisons(comparisons: &[Comparison]) {
+    let width = comparisons.iter().map(|comparison| comparison.zone.chars().count()).max().unwrap_or(0).max(4);
+    println!("{:<width$} {:>10} {:>10} {:>9}", "zone", "baseline", "current", "change", width = width);
+    for comparison in comparisons {
+        println!(
+            "{:<width$} {:>10} {:>10} {:>+8.1}%{}",
+            comparison.zone, Time(comparison.baseline), Time(comparison.current), comparison.change,
+            if comparison.regression { "  REGRESSION" } else { "" }, width = width,
+        );
+    }
+}
+
+/// 1 if a zone regressed, 0 otherwise.
+fn exit_status(comparisons: &[Comparison]) -> i32 {
+    if comparisons.iter().any(|comparison| comparison.regression) { 1 } else { 0 }
+}
+
+fn main() {
+    let options = parse_args().unwrap_or_else(|err| {
+        eprint!("{}\n\n{}", err, USAGE);
+        process::exit(2);
+    });
+
+    let current = open(&options.recording);
+    match &options.baseline {
+        Some(baseline) => {
+            let baseline = open(baseline);
+            let comparisons = compare(&options, &baseline, &current).unwrap_or_else(|err| {
+                eprintln!("{}", err);
+                process::exit(2);
+            });
+            print_comparisons(&comparisons);
+            process::exit(exit_status(&comparisons));
+        },
+        None => print_stats(&current, options.rows),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tracy_rs::{
+        file::{SourceLocation, Thread, Zone},
+        protocol::{Welcome, WELCOME_SIZE},
+    };
+
+    fn stats(name: &str, durations: Vec<i64>) -> (String, Stats) {
+        (name.to_string(), Stats::new(durations, 0))
+    }
+
+    fn compare_options(zones: &[&str], metric: Metric, threshold: f64) -> Options {
+        Options {
+            recording: "current".to_string(),
+            rows: None,
+            baseline: Some("baseline".to_string()),
+            zones: zones.iter().map(|zone| zone.to_string()).collect(),
+            metric,
+            threshold,
+        }
+    }
+
+    #[test]
+    fn percentiles() {
+        let stats = Stats::new((1..=100).rev().collect(), 7);
+        assert_eq!(stats.count, 100);
+        assert_eq!(stats.total, 5050);
+        assert_eq!(stats.mean, 50);
+        assert_eq!(stats.median, 50);
+        assert_eq!(stats.p90, 90);
+        assert_eq!(stats.p99, 99);
+        assert_eq!(stats.max, 100);
+        assert_eq!(stats.self_time, 7);
+
+        let stats = Stats::new(vec![3, 1, 2], 0);
+        assert_eq!((stats.mean, stats.median, stats.p90, stats.p99, stats.max), (2, 2, 3, 3, 3));
+        let stats = Stats::new(vec![5], 0);
+        assert_eq!((stats.mean, stats.median, stats.p90, stats.p99, stats.max), (5, 5, 5, 5, 5));
+    }
+
+    #[test]
+    fn self_time() {
+        let location = |name: &str| SourceLocation {
+            name: Some(name.to_string()),
+            function: String::new(),
+            file: String::new(),
+            line: 0,
+            color: 0,
+        };
+        let zone = |location, start, end: Option<i64>, parent| Zone {
+            location,
+            start,
+            end,
+            parent,
+            depth: 0,
+            name: None,
+            text: None,
+            color: None,
+            value: None,
+        };
+        let recording = Recording {
+            welcome: Welcome::parse(&[0; WELCOME_SIZE]),
+            app_info: Vec::new(),
+            source_locations: vec![location("update"), location("physics"), location("open")],
+            threads: vec![
+                Thread {
+                    id: 1,
+                    name: None,
+                    zones: vec![
+                        zone(0, 0, Some(100), None),
+                        zone(1, 10, Some(40), Some(0)),
+                        zone(1, 50, Some(60), Some(0)),
+                        //zones that didn't end are skipped, but still count as children
+                        zone(2, 70, None, Some(0)),
+                    ],
+                },
+                Thread {
+                    id: 2,
+                    name: None,
+                    zones: vec![zone(1, 0, Some(20), None)],
+                },
+            ],
+            frames: Vec::new(),
+            messages: Vec::new(),
+            plots: Vec::new(),
+            skipped_frames: 0,
+        };
+
+        let stats = zone_stats(&recording);
+        assert_eq!(stats.len(), 2);
+        assert_eq!((stats["update"].total, stats["update"].self_time), (100, 60));
+        assert_eq!((stats["physics"].count, stats["physics"].total, stats["physics"].self_time), (3, 60, 60));
+    }
+
+    #[test]
+    fn thresholds() {
+        let baseline: HashMap<_, _> = vec![
+            stats("a", vec![100]),
+            stats("b", vec![100]),
+            stats("c", vec![0]),
+            stats("old", vec![1]),
+        ].into_iter().collect();
+        let current: HashMap<_, _> = vec![
+            stats("a", vec![110]),
+            stats("b", vec![100, 111]),
+            stats("c", vec![1]),
+            stats("new", vec![1]),
+        ].into_iter().collect();
+
+        let options = compare_options(&[], Metric::Max, 10.0);
+        let comparisons = compare(&options, &baseline, &current).unwrap();
+        let result: Vec<_> = comparisons.iter().map(|comparison| (comparison.zone, comparison.regression)).collect();
+        //only the zones in both
+        assert_eq!(result, [("a", false), ("b", true), ("c", true)]);
+        assert_eq!(comparisons[0].change, 10.0);
+        assert_eq!(comparisons[2].change, f64::INFINITY);
+        assert_eq!(exit_status(&comparisons), 1);
+
+        let options = compare_options(&["a"], Metric::Max, 10.0);
+        let comparisons = compare(&options, &baseline, &current).unwrap();
+        assert_eq!(comparisons.len(), 1);
+        assert_eq!(exit_status(&comparisons), 0);
+
+        //the median of b is 100, its max 111
+        let options = compare_options(&["b"], Metric::Median, 10.0);
+        assert_eq!(exit_status(&compare(&options, &baseline, &current).unwrap()), 0);
+        let options = compare_options(&["b"], Metric::Max, 20.0);
+        assert_eq!(exit_status(&compare(&options, &baseline, &current).unwrap()), 0);
+    }
+
+    #[test]
+    fn missing_zones() {
+        let baseline: HashMap<_, _> = vec![stats("old", vec![1])].into_iter().collect();
+        let current: HashMap<_, _> = vec![stats("new", vec![1])].into_iter().collect();
+        let options = compare_options(&["new"], Metric::Median, 10.0);
+        assert_eq!(compare(&options, &baseline, &current), Err("baseline has no zone new".to_string()));
+        let options = compare_options(&["old"], Metric::Median, 10.0);
+        assert_eq!(compare(&options, &baseline, &current), Err("current has no zone old".to_string()));
+    }
+}
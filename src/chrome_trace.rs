@@ -0,0 +1,374 @@
+//! A backend that writes the events to a Chrome Trace Event Format JSON file, selected by the
+//! "chrome_trace" feature.
+//!
+//! The file can be loaded in [Perfetto](https://ui.perfetto.dev) or `chrome://tracing`.
+//! Events are buffered per thread and written by the first of `flush_and_wait`, the panic hook in
+//! `crate::panic` and the exit of the program, to the path in the `TRACY_CHROME_TRACE` environment
+//! variable or trace.json in the working directory. Nothing is recorded after that.
+//!
+//! Zones become duration events with their text, value and color as arguments, frame marks
+//! become instant or async events, messages become instant events and plots become counters.
+
+use crate::{
+    backend::{Backend, SourceCallback},
+    output::{lock, Output},
+    Color, SourceLocationData,
+};
+use libc::{c_int, c_void};
+use std::{
+    cell::RefCell,
+    env,
+    ffi::CStr,
+    fmt::Write as _,
+    fs::File,
+    io::{self, BufWriter, Write},
+    path::{Path, PathBuf},
+    process,
+    sync::{
+        atomic::{AtomicU32, AtomicU64, Ordering},
+        Arc, Mutex, OnceLock,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+/// The serialized events of each thread that recorded any, separated by commas.
+static OUTPUT: Output<String> = Output::new("the Chrome trace", output_path, write_output, write_at_exit);
+static APP_INFO: Mutex<String> = Mutex::new(String::new());
+static START: OnceLock<Instant> = OnceLock::new();
+static NEXT_TID: AtomicU64 = AtomicU64::new(1);
+static NEXT_ZONE_ID: AtomicU32 = AtomicU32::new(1);
+
+struct ThreadBuffer {
+    tid: u64,
+    events: Arc<Mutex<String>>,
+    /// The arguments of the open zones by id, which are attached to their end events.
+    zone_args: Vec<(u32, String)>,
+}
+
+impl ThreadBuffer {
+    fn new() -> Self {
+        let mut buffer = Self {
+            tid: NEXT_TID.fetch_add(1, Ordering::Relaxed),
+            events: OUTPUT.register(String::new()),
+            zone_args: Vec::new(),
+        };
+        if let Some(name) = thread::current().name() {
+            let name = escape(name);
+            buffer.event(format_args!(
+                r#""ph":"M","name":"thread_name","args":{{"name":"{}"}}"#, name,
+            ));
+        }
+        buffer
+    }
+
+    /// Appends an event with the process and thread ids.
+    fn event(&mut self, fields: std::fmt::Arguments) {
+        let mut events = lock(&self.events);
+        if !events.is_empty() {
+            events.push_str(",\n");
+        }
+        write!(events, r#"{{"pid":{},"tid":{},{}}}"#, process::id(), self.tid, fields).unwrap();
+    }
+}
+
+thread_local! {
+    static BUFFER: RefCell<ThreadBuffer> = RefCell::new(ThreadBuffer::new());
+}
+
+fn with_buffer(f: impl FnOnce(&mut ThreadBuffer)) {
+    if OUTPUT.is_stopped() {
+        return;
+    }
+    //the buffer is gone while the thread is exiting
+    let _ = BUFFER.try_with(|buffer| f(&mut buffer.borrow_mut()));
+}
+
+/// Microseconds since the first event.
+fn timestamp() -> f64 {
+    START.get_or_init(Instant::now).elapsed().as_nanos() as f64 / 1000.0
+}
+
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            c if (c as u32) < 0x20 => write!(escaped, "\\u{:04x}", c as u32).unwrap(),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+fn color_hex(color: Color) -> String {
+    format!("#{:06x}", color.to_u32() & 0xffffff)
+}
+
+/// The path the trace is written to by `flush_and_wait` or at exit.
+pub fn output_path() -> PathBuf {
+    env::var_os("TRACY_CHROME_TRACE").map_or_else(|| PathBuf::from("trace.json"), PathBuf::from)
+}
+
+/// Writes the events recorded so far by all threads to `path`, without stopping the recording.
+pub fn write(path: impl AsRef<Path>) -> io::Result<()> {
+    let mut file = BufWriter::new(File::create(path)?);
+    file.write_all(b"{\"traceEvents\":[\n")?;
+    let mut first = true;
+    for events in OUTPUT.threads() {
+        let events = lock(&events);
+        if events.is_empty() {
+            continue;
+        }
+        if !first {
+            file.write_all(b",\n")?;
+        }
+        first = false;
+        file.write_all(events.as_bytes())?;
+    }
+    let app_info = lock(&APP_INFO);
+    write!(file, "\n],\"displayTimeUnit\":\"ns\",\"otherData\":{{\"app_info\":\"{}\"}}}}\n", escape(&app_info))?;
+    file.flush()
+}
+
+fn write_output(path: &Path) -> io::Result<()> {
+    write(path)
+}
+
+extern "C" fn write_at_exit() {
+    OUTPUT.write_and_stop();
+}
+
+/// A zone of `ChromeTrace`.
+#[derive(Clone, Copy, Debug)]
+pub struct Zone {
+    id: u32,
+    active: bool,
+}
+
+/// Writes the events to a Chrome Trace Event Format JSON file.
+pub enum ChromeTrace {}
+
+impl ChromeTrace {
+    fn zone_arg(zone: Zone, arg: std::fmt::Arguments) {
+        if zone.active {
+            with_buffer(|buffer| {
+                let args = match buffer.zone_args.iter_mut().rev().find(|(id, _)| *id == zone.id) {
+                    Some((_, args)) => {
+                        args.push(',');
+                        args
+                    },
+                    None => {
+                        buffer.zone_args.push((zone.id, String::new()));
+                        &mut buffer.zone_args.last_mut().unwrap().1
+                    },
+                };
+                args.write_fmt(arg).unwrap();
+            });
+        }
+    }
+}
+
+impl Backend for ChromeTrace {
+    type Zone = Zone;
+
+    fn zone_begin(loc: &SourceLocationData<'_>, _callstack_depth: c_int, active: bool) -> Self::Zone {
+        let zone = Zone { id: NEXT_ZONE_ID.fetch_add(1, Ordering::Relaxed), active };
+        if active {
+            let name = escape(&loc.name().unwrap_or_else(|| loc.function()).to_string_lossy());
+            let function = escape(&loc.function().to_string_lossy());
+            let file = escape(&loc.file().to_string_lossy());
+            let ts = timestamp();
+            with_buffer(|buffer| buffer.event(format_args!(
+                r#""ph":"B","ts":{:.3},"name":"{}","args":{{"function":"{}","file":"{}","line":{}}}"#,
+                ts, name, function, file, loc.line(),
+            )));
+        }
+        zone
+    }
+
+    fn zone_end(zone: Self::Zone) {
+        if zone.active {
+            let ts = timestamp();
+            with_buffer(|buffer| {
+                let args = buffer.zone_args.iter().rposition(|(id, _)| *id == zone.id)
+                    .map(|i| buffer.zone_args.remove(i).1);
+                match args {
+                    Some(args) => buffer.event(format_args!(r#""ph":"E","ts":{:.3},"args":{{{}}}"#, ts, args)),
+                    None => buffer.event(format_args!(r#""ph":"E","ts":{:.3}"#, ts)),
+                }
+            });
+        }
+    }
+
+    #[inline]
+    fn zone_is_active(zone: Self::Zone) -> bool {
+        zone.active
+    }
+
+    fn zone_text(zone: Self::Zone, text: &str) {
+        Self::zone_arg(zone, format_args!(r#""text":"{}""#, escape(text)));
+    }
+
+    fn zone_color(zone: Self::Zone, color: Color) {
+        Self::zone_arg(zone, format_args!(r#""color":"{}""#, color_hex(color)));
+    }
+
+    fn zone_value(zone: Self::Zone, value: u64) {
+        Self::zone_arg(zone, format_args!(r#""value":{}"#, value));
+    }
+
+    fn frame_mark(name: Option<&'static CStr>) {
+        let name = name.map_or_else(|| "frame".to_string(), |name| escape(&name.to_string_lossy()));
+        let ts = timestamp();
+        with_buffer(|buffer| buffer.event(format_args!(
+            r#""ph":"i","s":"p","ts":{:.3},"cat":"frame","name":"{}""#, ts, name,
+        )));
+    }
+
+    fn frame_mark_start(name: &'static CStr) {
+        let name = escape(&name.to_string_lossy());
+        let ts = timestamp();
+        with_buffer(|buffer| buffer.event(format_args!(
+            r#""ph":"b","ts":{:.3},"cat":"frame","id":"{}","name":"{}""#, ts, name, name,
+        )));
+    }
+
+    fn frame_mark_end(name: &'static CStr) {
+        let name = escape(&name.to_string_lossy());
+        let ts = timestamp();
+        with_buffer(|buffer| buffer.event(format_args!(
+            r#""ph":"e","ts":{:.3},"cat":"frame","id":"{}","name":"{}""#, ts, name, name,
+        )));
+    }
+
+    fn message(text: &str, color: Option<Color>, _callstack_depth: c_int) {
+        let text = escape(text);
+        let ts = timestamp();
+        with_buffer(|buffer| match color {
+            Some(color) => buffer.event(format_args!(
+                r#""ph":"i","s":"t","ts":{:.3},"cat":"message","name":"{}","args":{{"color":"{}"}}"#,
+                ts, text, color_hex(color),
+            )),
+            None => buffer.event(format_args!(
+                r#""ph":"i","s":"t","ts":{:.3},"cat":"message","name":"{}""#, ts, text,
+            )),
+        });
+    }
+
+    fn app_info(info: &str) {
+        if let Ok(mut app_info) = APP_INFO.lock() {
+            if !app_info.is_empty() {
+                app_info.push('\n');
+            }
+            app_info.push_str(info);
+        }
+    }
+
+    fn plot(name: &'static CStr, value: f64) {
+        let name = escape(&name.to_string_lossy());
+        let ts = timestamp();
+        //JSON has no representation for NaN and infinities
+        let value = if value.is_finite() { value } else { 0.0 };
+        with_buffer(|buffer| buffer.event(format_args!(
+            r#""ph":"C","ts":{:.3},"name":"{}","args":{{"value":{}}}"#, ts, name, value,
+        )));
+    }
+
+    unsafe fn frame_image(_image: *const c_void, _width: u16, _height: u16, _offset: u8, _flip: bool) {}
+
+    fn memory_alloc(_ptr: *const u8, _size: usize, _callstack_depth: c_int) {}
+
+    fn memory_free(_ptr: *const u8, _callstack_depth: c_int) {}
+
+    fn parameter_register(_changed: fn(index: u32, value: i32)) {}
+
+    fn parameter_setup(_index: u32, _name: &'static CStr, _is_bool: bool, _value: i32) {}
+
+    fn source_callback_register(_callback: &'static SourceCallback) {}
+
+    /// Writes the trace to `output_path`; it is written at once, so `_timeout` doesn't apply.
+    fn flush(_timeout: Duration) -> bool {
+        OUTPUT.write_and_stop()
+    }
+
+    /// The trace is always recorded.
+    #[inline]
+    fn is_connected() -> bool {
+        true
+    }
+
+    #[inline]
+    fn listen_port() -> Option<u16> {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{loc, cstr};
+
+    /// The events recorded by `f` on a new thread, with the timestamps replaced by 0.
+    fn thread_events(f: impl FnOnce() + Send + 'static) -> String {
+        thread::spawn(move || {
+            f();
+            let events = BUFFER.with(|buffer| buffer.borrow().events.lock().unwrap().clone());
+            let tid = BUFFER.with(|buffer| buffer.borrow().tid);
+            let events = events.replace(&format!(r#""pid":{},"tid":{},"#, process::id(), tid), "");
+            let mut parts = events.split(r#""ts":"#);
+            let mut normalized = parts.next().unwrap().to_string();
+            for part in parts {
+                normalized.push_str(r#""ts":0"#);
+                normalized.push_str(&part[part.find(',').unwrap()..]);
+            }
+            normalized
+        }).join().unwrap()
+    }
+
+    #[test]
+    fn escapes() {
+        assert_eq!(escape("plain"), "plain");
+        assert_eq!(escape(r#"say "hi" \ bye"#), r#"say \"hi\" \\ bye"#);
+        assert_eq!(escape("a\nb\r\tc"), r"a\nb\r\tc");
+        assert_eq!(escape("\u{1}\u{1f}"), r"\u0001\u001f");
+        assert_eq!(escape("ünïcode"), "ünïcode");
+    }
+
+    #[test]
+    fn events() {
+        let loc = loc!();
+        let line = loc.line;
+        let events = thread_events(move || {
+            let loc = SourceLocationData::with_name(&loc, cstr!("update \"world\""));
+            let zone = ChromeTrace::zone_begin(&loc, 0, true);
+            ChromeTrace::zone_text(zone, "text");
+            ChromeTrace::zone_value(zone, 42);
+            ChromeTrace::zone_color(zone, Color::from_u32(0xff0000));
+            let inactive = ChromeTrace::zone_begin(&loc, 0, false);
+            ChromeTrace::zone_text(inactive, "ignored");
+            ChromeTrace::zone_end(inactive);
+            ChromeTrace::zone_end(zone);
+            ChromeTrace::frame_mark(None);
+            ChromeTrace::frame_mark_start(cstr!("load"));
+            ChromeTrace::frame_mark_end(cstr!("load"));
+            ChromeTrace::message("hello", Some(Color::from_u32(0x00ff00)), 0);
+            ChromeTrace::plot(cstr!("fps"), f64::NAN);
+        });
+        let lines: Vec<_> = events.lines().collect();
+        assert_eq!(lines, [
+            format!(r#"{{"ph":"B","ts":0,"name":"update \"world\"","args":{{"function":"{}","file":"{}","line":{}}}}},"#,
+                concat!(module_path!(), "::fn(?)"), escape(file!()), line),
+            r##"{"ph":"E","ts":0,"args":{"text":"text","value":42,"color":"#ff0000"}},"##.to_string(),
+            r#"{"ph":"i","s":"p","ts":0,"cat":"frame","name":"frame"},"#.to_string(),
+            r#"{"ph":"b","ts":0,"cat":"frame","id":"load","name":"load"},"#.to_string(),
+            r#"{"ph":"e","ts":0,"cat":"frame","id":"load","name":"load"},"#.to_string(),
+            r##"{"ph":"i","s":"t","ts":0,"cat":"message","name":"hello","args":{"color":"#00ff00"}},"##.to_string(),
+            r#"{"ph":"C","ts":0,"name":"fps","args":{"value":0}}"#.to_string(),
+        ]);
+    }
+}
@@ -0,0 +1,99 @@
+//! Replaces Tracy's demangler, which only knows C++, for the symbols in captured callstacks.
+
+use libc::{c_char, c_int, size_t};
+use std::{cell::RefCell, ffi::{CStr, CString}, ptr};
+
+#[cfg(unix)]
+extern "C" {
+    // from the C++ runtime the Tracy client links against anyway
+    fn __cxa_demangle(mangled: *const c_char, buf: *mut c_char, len: *mut size_t, status: *mut c_int) -> *mut c_char;
+}
+
+thread_local! {
+    static DEMANGLED: RefCell<CString> = RefCell::new(CString::default());
+}
+
+fn demangle(mangled: &str) -> Option<String> {
+    if let Ok(symbol) = rustc_demangle::try_demangle(mangled) {
+        //the alternate format leaves out the hash
+        return Some(format!("{:#}", symbol));
+    }
+
+    #[cfg(unix)]
+    {
+        if mangled.starts_with("_Z") {
+            let mangled = CString::new(mangled).ok()?;
+            let mut status = 0;
+            unsafe {
+                let demangled = __cxa_demangle(mangled.as_ptr(), ptr::null_mut(), ptr::null_mut(), &mut status);
+                if status == 0 && !demangled.is_null() {
+                    let result = CStr::from_ptr(demangled).to_string_lossy().into_owned();
+                    libc::free(demangled as *mut _);
+                    return Some(result);
+                }
+            }
+        }
+    }
+
+    None
+}
+
+/// Called by the Tracy client, which is built with TRACY_DEMANGLE.
+///
+/// The result stays valid until the next call from the same thread; null leaves the symbol as it is.
+#[no_mangle]
+pub extern "C" fn ___tracy_demangle(mangled: *const c_char) -> *const c_char {
+    if mangled.is_null() {
+        return ptr::null();
+    }
+    let mangled = match unsafe{ CStr::from_ptr(mangled) }.to_str() {
+        Ok(mangled) => mangled,
+        Err(_) => return ptr::null(),
+    };
+    match demangle(mangled).and_then(|demangled| CString::new(demangled).ok()) {
+        Some(demangled) => DEMANGLED.with(|buffer| {
+            let mut buffer = buffer.borrow_mut();
+            *buffer = demangled;
+            buffer.as_ptr()
+        }),
+        None => ptr::null(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn demangled(mangled: &[u8]) -> Option<String> {
+        let mangled = CString::new(mangled).unwrap();
+        let demangled = ___tracy_demangle(mangled.as_ptr());
+        if demangled.is_null() {
+            None
+        } else {
+            Some(unsafe{ CStr::from_ptr(demangled) }.to_str().unwrap().to_string())
+        }
+    }
+
+    #[test]
+    fn rust_symbols() {
+        assert_eq!(demangled(b"_ZN4core3fmt9Formatter9write_str17h0123456789abcdefE").as_deref(), Some("core::fmt::Formatter::write_str"));
+        assert_eq!(demangled(b"_RNvCs15kBYyAo9fc_7mycrate7example").as_deref(), Some("mycrate::example"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn cpp_symbols() {
+        assert_eq!(demangled(b"_Z3fooi").as_deref(), Some("foo(int)"));
+        assert_eq!(demangled(b"_ZN5tracy8Profiler6WorkerEv").as_deref(), Some("tracy::Profiler::Worker()"));
+    }
+
+    #[test]
+    fn left_as_is() {
+        assert!(___tracy_demangle(ptr::null()).is_null());
+        assert_eq!(demangled(b"main"), None);
+        assert_eq!(demangled(b""), None);
+        assert_eq!(demangled(b"_ZN3foo"), None);
+        assert_eq!(demangled(b"_RNvC"), None);
+        assert_eq!(demangled(b"_Z\xff"), None);
+    }
+}
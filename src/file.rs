@@ -0,0 +1,659 @@
+//! Reads the recordings written by tracy-record.
+//!
+//! These contain the data as received from the client, see `protocol::RECORDING_MAGIC`; the .tracy
+//! files saved by the viewer are a different format and can't be read.
+//!
+//! ```no_run
+//! let recording = tracy_rs::file::Recording::open("trace.recording").unwrap();
+//! for thread in &recording.threads {
+//!     for zone in &thread.zones {
+//!         let location = &recording.source_locations[zone.location];
+//!         println!("{} {:?}", location.name(), zone.duration());
+//!     }
+//! }
+//! ```
+//!
+//! All times are in nanoseconds since the client was initialized.
+//! Only zones, frame marks, messages, plots and application information are read; locks, memory
+//! events, GPU zones, callstacks and sampling data are skipped.
+
+use crate::protocol::{self, Decompressor, Error, Event, Events, QueueType, ServerQuery, Welcome};
+use std::{
+    collections::{HashMap, VecDeque},
+    convert::TryInto,
+    fs::File,
+    io::{self, BufReader, Read},
+    path::Path,
+};
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct SourceLocation {
+    pub name: Option<String>,
+    pub function: String,
+    pub file: String,
+    pub line: u32,
+    /// 0 for the default color, 0xRRGGBB otherwise.
+    pub color: u32,
+}
+
+impl SourceLocation {
+    /// The name of the zone, or its function if it has none.
+    pub fn name(&self) -> &str {
+        self.name.as_deref().unwrap_or(&self.function)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Zone {
+    /// The index into `Recording::source_locations`.
+    pub location: usize,
+    pub start: i64,
+    /// `None` if the zone didn't end before the recording did.
+    pub end: Option<i64>,
+    /// The index of the enclosing zone in `Thread::zones`.
+    pub parent: Option<usize>,
+    pub depth: u32,
+    /// Set with `ZoneName`, overriding the name of the source location.
+    pub name: Option<String>,
+    pub text: Option<String>,
+    pub color: Option<u32>,
+    pub value: Option<u64>,
+}
+
+impl Zone {
+    #[inline]
+    pub fn duration(&self) -> Option<i64> {
+        self.end.map(|end| end - self.start)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Thread {
+    pub id: u32,
+    pub name: Option<String>,
+    /// In the order they began.
+    pub zones: Vec<Zone>,
+}
+
+/// A frame, from one frame mark to the next, or from `frame_mark_start!` to `frame_mark_end!`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Frame {
+    /// `None` for the main frames.
+    pub name: Option<String>,
+    pub start: i64,
+    pub end: i64,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Message {
+    pub thread: u32,
+    pub time: i64,
+    pub text: String,
+    /// 0xRRGGBB
+    pub color: Option<u32>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Plot {
+    pub name: String,
+    /// The time and value of the data points.
+    pub points: Vec<(i64, f64)>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Recording {
+    pub welcome: Welcome,
+    pub app_info: Vec<String>,
+    pub source_locations: Vec<SourceLocation>,
+    pub threads: Vec<Thread>,
+    pub frames: Vec<Frame>,
+    pub messages: Vec<Message>,
+    pub plots: Vec<Plot>,
+    /// The frames that contained an event that couldn't be read; their remaining events are missing.
+    pub skipped_frames: usize,
+}
+
+impl Recording {
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, Error> {
+        Self::read(BufReader::new(File::open(path)?))
+    }
+
+    pub fn read(mut reader: impl Read) -> Result<Self, Error> {
+        let mut magic = [0; 8];
+        reader.read_exact(&mut magic)?;
+        if &magic != protocol::RECORDING_MAGIC {
+            return Err(Error::Format("not a recording written by tracy-record".to_string()));
+        }
+        let version = read_u32(&mut reader)?;
+        if version != protocol::RECORDING_VERSION {
+            return Err(Error::Format(format!("unsupported recording version {}", version)));
+        }
+        let protocol_version = read_u32(&mut reader)?;
+        if protocol_version != crate::PROTOCOL_VERSION {
+            return Err(Error::Format(format!(
+                "the recording uses protocol version {}, but tracy-rs was built for version {}",
+                protocol_version, crate::PROTOCOL_VERSION,
+            )));
+        }
+        let mut welcome = [0; protocol::WELCOME_SIZE];
+        reader.read_exact(&mut welcome)?;
+        let mut on_demand = [0];
+        reader.read_exact(&mut on_demand)?;
+        if on_demand[0] != 0 {
+            reader.read_exact(&mut [0; protocol::ON_DEMAND_PAYLOAD_SIZE])?;
+        }
+
+        let mut builder = Builder::new(Welcome::parse(&welcome));
+        let mut decompressor = Decompressor::new();
+        let mut frame = Vec::new();
+        loop {
+            let mut tag = [0];
+            match reader.read_exact(&mut tag) {
+                Ok(()) => (),
+                Err(err) if err.kind() == io::ErrorKind::UnexpectedEof => break,
+                Err(err) => return Err(err.into()),
+            }
+            match tag[0] {
+                protocol::RECORD_QUERY => {
+                    let mut query = [0; 9];
+                    reader.read_exact(&mut query)?;
+                    let ty = ServerQuery::from_u8(query[0])
+                        .ok_or_else(|| Error::Format(format!("unknown query {}", query[0])))?;
+                    builder.queries.push_back((ty, u64::from_le_bytes(query[1..].try_into().unwrap())));
+                },
+                protocol::RECORD_FRAME => {
+                    frame.resize(read_u32(&mut reader)? as usize, 0);
+                    reader.read_exact(&mut frame)?;
+                    for event in Events::new(decompressor.decompress(&frame)?) {
+                        match event {
+                            Ok(event) => builder.event(&event),
+                            Err(_) => {
+                                builder.recording.skipped_frames += 1;
+                                break;
+                            },
+                        }
+                    }
+                },
+                tag => return Err(Error::Format(format!("unknown record {}", tag))),
+            }
+        }
+        Ok(builder.finish())
+    }
+}
+
+fn read_u32(reader: &mut impl Read) -> io::Result<u32> {
+    let mut bytes = [0; 4];
+    reader.read_exact(&mut bytes)?;
+    Ok(u32::from_le_bytes(bytes))
+}
+
+fn rgb(b: u8, g: u8, r: u8) -> u32 {
+    (r as u32) << 16 | (g as u32) << 8 | b as u32
+}
+
+/// A source location before its strings are resolved.
+enum RawLocation {
+    /// The address of a static source location.
+    Static(u64),
+    /// A source location sent along with its zone.
+    Alloc(SourceLocation),
+}
+
+/// A frame mark before its name is resolved.
+struct RawFrameMark {
+    ty: QueueType,
+    time: i64,
+    name: u64,
+}
+
+struct Builder {
+    recording: Recording,
+    threads: HashMap<u32, usize>,
+    thread: usize,
+    /// The open zones of each thread, as indices into `Thread::zones`.
+    open: Vec<Vec<usize>>,
+    ref_time: i64,
+    single_string: Option<String>,
+    location_payload: Option<SourceLocation>,
+    raw_locations: Vec<RawLocation>,
+    static_locations: HashMap<u64, usize>,
+    /// The queries tracy-record sent that weren't answered yet, in the order of the replies.
+    queries: VecDeque<(ServerQuery, u64)>,
+    location_replies: HashMap<u64, (u64, u64, u64, u32, u32)>,
+    strings: HashMap<u64, String>,
+    thread_names: HashMap<u64, String>,
+    plot_names: HashMap<u64, String>,
+    frame_names: HashMap<u64, String>,
+    plots: HashMap<u64, Vec<(i64, f64)>>,
+    frame_marks: Vec<RawFrameMark>,
+    literal_messages: Vec<(usize, u64)>,
+}
+
+impl Builder {
+    fn new(welcome: Welcome) -> Self {
+        Self {
+            recording: Recording {
+                welcome,
+                app_info: Vec::new(),
+                source_locations: Vec::new(),
+                threads: Vec::new(),
+                frames: Vec::new(),
+                messages: Vec::new(),
+                plots: Vec::new(),
+                skipped_frames: 0,
+            },
+            threads: HashMap::new(),
+            thread: usize::MAX,
+            open: Vec::new(),
+            ref_time: 0,
+            single_string: None,
+            location_payload: None,
+            raw_locations: Vec::new(),
+            static_locations: HashMap::new(),
+            queries: VecDeque::new(),
+            location_replies: HashMap::new(),
+            strings: HashMap::new(),
+            thread_names: HashMap::new(),
+            plot_names: HashMap::new(),
+            frame_names: HashMap::new(),
+            plots: HashMap::new(),
+            frame_marks: Vec::new(),
+            literal_messages: Vec::new(),
+        }
+    }
+
+    fn time(&self, time: i64) -> i64 {
+        self.recording.welcome.to_ns(time)
+    }
+
+    /// Adds `dt` to the reference time of the thread, against which zones and plots are sent.
+    fn ref_time(&mut self, dt: i64) -> i64 {
+        self.ref_time += dt;
+        self.time(self.ref_time)
+    }
+
+    fn thread_id(&self) -> u32 {
+        self.recording.threads.get(self.thread).map_or(0, |thread| thread.id)
+    }
+
+    fn top_zone(&mut self) -> Option<&mut Zone> {
+        let thread = self.thread;
+        let &zone = self.open.get(thread)?.last()?;
+        Some(&mut self.recording.threads[thread].zones[zone])
+    }
+
+    fn begin_zone(&mut self, location: RawLocation, dt: i64) {
+        let start = self.ref_time(dt);
+        let location = match location {
+            RawLocation::Static(ptr) => match self.static_locations.get(&ptr) {
+                Some(&location) => location,
+                None => {
+                    self.raw_locations.push(RawLocation::Static(ptr));
+                    self.static_locations.insert(ptr, self.raw_locations.len() - 1);
+                    self.raw_locations.len() - 1
+                },
+            },
+            location => {
+                self.raw_locations.push(location);
+                self.raw_locations.len() - 1
+            },
+        };
+        if self.thread >= self.recording.threads.len() {
+            self.set_thread(0);
+        }
+        let open = &mut self.open[self.thread];
+        let zones = &mut self.recording.threads[self.thread].zones;
+        zones.push(Zone {
+            location,
+            start,
+            end: None,
+            parent: open.last().copied(),
+            depth: open.len() as u32,
+            name: None,
+            text: None,
+            color: None,
+            value: None,
+        });
+        open.push(zones.len() - 1);
+    }
+
+    fn set_thread(&mut self, id: u32) {
+        let threads = &mut self.recording.threads;
+        let open = &mut self.open;
+        self.thread = *self.threads.entry(id).or_insert_with(|| {
+            threads.push(Thread { id, name: None, zones: Vec::new() });
+            open.push(Vec::new());
+            threads.len() - 1
+        });
+        self.ref_time = 0;
+    }
+
+    fn event(&mut self, event: &Event) {
+        //source locations are the only replies that don't say what they answer
+        let query = if protocol::answers_query(event) { self.queries.pop_front() } else { None };
+
+        let mut reader = event.reader();
+        match event.ty {
+            QueueType::ThreadContext => self.set_thread(reader.u32()),
+            QueueType::ZoneBegin | QueueType::ZoneBeginCallstack => {
+                let dt = reader.i64();
+                self.begin_zone(RawLocation::Static(reader.u64()), dt);
+            },
+            QueueType::ZoneBeginAllocSrcLoc | QueueType::ZoneBeginAllocSrcLocCallstack => {
+                let dt = reader.i64();
+                if let Some(location) = self.location_payload.take() {
+                    self.begin_zone(RawLocation::Alloc(location), dt);
+                }
+            },
+            QueueType::ZoneEnd => {
+                let end = self.ref_time(reader.i64());
+                if let Some(open) = self.open.get_mut(self.thread) {
+                    if let Some(zone) = open.pop() {
+                        self.recording.threads[self.thread].zones[zone].end = Some(end);
+                    }
+                }
+            },
+            QueueType::ZoneText => {
+                let text = self.single_string.take();
+                if let Some(zone) = self.top_zone() {
+                    zone.text = text;
+                }
+            },
+            QueueType::ZoneName => {
+                let name = self.single_string.take();
+                if let Some(zone) = self.top_zone() {
+                    zone.name = name;
+                }
+            },
+            QueueType::ZoneColor => {
+                let color = rgb(reader.u8(), reader.u8(), reader.u8());
+                if let Some(zone) = self.top_zone() {
+                    zone.color = Some(color);
+                }
+            },
+            QueueType::ZoneValue => {
+                let value = reader.u64();
+                if let Some(zone) = self.top_zone() {
+                    zone.value = Some(value);
+                }
+            },
+            QueueType::Message | QueueType::MessageCallstack => {
+                let time = self.time(reader.i64());
+                let text = self.single_string.take().unwrap_or_default();
+                self.recording.messages.push(Message { thread: self.thread_id(), time, text, color: None });
+            },
+            QueueType::MessageColor | QueueType::MessageColorCallstack => {
+                let time = self.time(reader.i64());
+                let color = rgb(reader.u8(), reader.u8(), reader.u8());
+                let text = self.single_string.take().unwrap_or_default();
+                self.recording.messages.push(Message { thread: self.thread_id(), time, text, color: Some(color) });
+            },
+            QueueType::MessageLiteral | QueueType::MessageLiteralCallstack
+            | QueueType::MessageLiteralColor | QueueType::MessageLiteralColorCallstack => {
+                let time = self.time(reader.i64());
+                let color = match event.ty {
+                    QueueType::MessageLiteralColor | QueueType::MessageLiteralColorCallstack => {
+                        Some(rgb(reader.u8(), reader.u8(), reader.u8()))
+                    },
+                    _ => None,
+                };
+                let text = reader.u64();
+                self.literal_messages.push((self.recording.messages.len(), text));
+                self.recording.messages.push(Message { thread: self.thread_id(), time, text: String::new(), color });
+            },
+            QueueType::MessageAppInfo => {
+                self.recording.app_info.extend(self.single_string.take());
+            },
+            QueueType::FrameMarkMsg | QueueType::FrameMarkMsgStart | QueueType::FrameMarkMsgEnd => {
+                let time = self.time(reader.i64());
+                self.frame_marks.push(RawFrameMark { ty: event.ty, time, name: reader.u64() });
+            },
+            QueueType::PlotDataInt | QueueType::PlotDataFloat | QueueType::PlotDataDouble => {
+                let name = reader.u64();
+                let time = self.ref_time(reader.i64());
+                let value = match event.ty {
+                    QueueType::PlotDataInt => reader.i64() as f64,
+                    QueueType::PlotDataFloat => reader.f32() as f64,
+                    _ => reader.f64(),
+                };
+                self.plots.entry(name).or_default().push((time, value));
+            },
+            QueueType::SourceLocation => {
+                if let Some((ServerQuery::SourceLocation, ptr)) = query {
+                    let (name, function, file, line) = (reader.u64(), reader.u64(), reader.u64(), reader.u32());
+                    let color = rgb(reader.u8(), reader.u8(), reader.u8());
+                    self.location_replies.insert(ptr, (name, function, file, line, color));
+                }
+            },
+            QueueType::SingleStringData => self.single_string = Some(event.text()),
+            QueueType::SourceLocationPayload => {
+                self.location_payload = parse_location_payload(event.string.unwrap_or_default());
+            },
+            QueueType::StringData => {
+                self.strings.insert(reader.u64(), event.text());
+            },
+            QueueType::ThreadName => {
+                self.thread_names.insert(reader.u64(), event.text());
+            },
+            QueueType::PlotName => {
+                self.plot_names.insert(reader.u64(), event.text());
+            },
+            QueueType::FrameName => {
+                self.frame_names.insert(reader.u64(), event.text());
+            },
+            _ => (),
+        }
+    }
+
+    fn finish(mut self) -> Recording {
+        let strings = &self.strings;
+        let string = |ptr: u64| strings.get(&ptr).cloned();
+        let unknown = |ptr: u64| string(ptr).unwrap_or_else(|| format!("<unknown {:#x}>", ptr));
+
+        let location_replies = &self.location_replies;
+        self.recording.source_locations = self.raw_locations.into_iter()
+            .map(|location| match location {
+                RawLocation::Static(ptr) => match location_replies.get(&ptr) {
+                    Some(&(name, function, file, line, color)) => SourceLocation {
+                        name: if name == 0 { None } else { Some(unknown(name)) },
+                        function: unknown(function),
+                        file: unknown(file),
+                        line,
+                        color,
+                    },
+                    None => SourceLocation {
+                        name: None,
+                        function: unknown(ptr),
+                        file: String::new(),
+                        line: 0,
+                        color: 0,
+                    },
+                },
+                RawLocation::Alloc(location) => location,
+            })
+            .collect();
+
+        for thread in &mut self.recording.threads {
+            thread.name = self.thread_names.get(&(thread.id as u64)).cloned();
+        }
+
+        for (message, text) in self.literal_messages {
+            self.recording.messages[message].text = unknown(text);
+        }
+
+        let plot_names = &self.plot_names;
+        self.recording.plots = self.plots.into_iter()
+            .map(|(name, points)| Plot {
+                name: plot_names.get(&name).cloned().unwrap_or_else(|| format!("<unknown {:#x}>", name)),
+                points,
+            })
+            .collect();
+        self.recording.plots.sort_by(|a, b| a.name.cmp(&b.name));
+
+        //continuous frames last until the next mark of the same name
+        let mut last = HashMap::new();
+        let mut started = HashMap::new();
+        for mark in self.frame_marks {
+            let name = if mark.name == 0 {
+                None
+            } else {
+                Some(self.frame_names.get(&mark.name).cloned().unwrap_or_else(|| format!("<unknown {:#x}>", mark.name)))
+            };
+            match mark.ty {
+                QueueType::FrameMarkMsg => {
+                    if let Some(start) = last.insert(mark.name, mark.time) {
+                        self.recording.frames.push(Frame { name, start, end: mark.time });
+                    }
+                },
+                QueueType::FrameMarkMsgStart => {
+                    started.insert(mark.name, mark.time);
+                },
+                _ => {
+                    if let Some(start) = started.remove(&mark.name) {
+                        self.recording.frames.push(Frame { name, start, end: mark.time });
+                    }
+                },
+            }
+        }
+
+        self.recording
+    }
+}
+
+/// The color and line as `u32`, the function and file terminated by 0, and the name.
+fn parse_location_payload(payload: &[u8]) -> Option<SourceLocation> {
+    if payload.len() < 8 {
+        return None;
+    }
+    let color = u32::from_le_bytes([payload[0], payload[1], payload[2], payload[3]]);
+    let line = u32::from_le_bytes([payload[4], payload[5], payload[6], payload[7]]);
+    let mut strings = payload[8..].splitn(3, |&b| b == 0);
+    let function = String::from_utf8_lossy(strings.next()?).into_owned();
+    let file = String::from_utf8_lossy(strings.next()?).into_owned();
+    let name = strings.next().filter(|name| !name.is_empty())
+        .map(|name| String::from_utf8_lossy(name).into_owned());
+    Some(SourceLocation { name, function, file, line, color })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(events: &mut Vec<u8>, ty: QueueType, data: &[&[u8]], string: Option<&str>) {
+        events.push(ty as u8);
+        data.iter().for_each(|data| events.extend_from_slice(data));
+        if let Some(string) = string {
+            events.extend_from_slice(&(string.len() as u16).to_le_bytes());
+            events.extend_from_slice(string.as_bytes());
+        }
+    }
+
+    fn source_location(events: &mut Vec<u8>, name: u64, function: u64, file: u64, line: u32) {
+        event(events, QueueType::SourceLocation, &[
+            &name.to_le_bytes(), &function.to_le_bytes(), &file.to_le_bytes(), &line.to_le_bytes(), &[1, 2, 3],
+        ], None);
+    }
+
+    /// A frame record of only literals.
+    fn frame(events: &[u8]) -> Vec<u8> {
+        let mut frame = vec![0xf0];
+        let mut len = events.len() - 15;
+        while len >= 255 {
+            frame.push(255);
+            len -= 255;
+        }
+        frame.push(len as u8);
+        frame.extend_from_slice(events);
+
+        let mut record = vec![protocol::RECORD_FRAME];
+        record.extend_from_slice(&(frame.len() as u32).to_le_bytes());
+        record.extend_from_slice(&frame);
+        record
+    }
+
+    fn query(query: ServerQuery, ptr: u64) -> Vec<u8> {
+        let mut record = vec![protocol::RECORD_QUERY, query as u8];
+        record.extend_from_slice(&ptr.to_le_bytes());
+        record
+    }
+
+    fn read(records: &[Vec<u8>]) -> Result<Recording, Error> {
+        let mut welcome = [0; protocol::WELCOME_SIZE];
+        welcome[..8].copy_from_slice(&1f64.to_le_bytes());
+        let mut recording = protocol::RECORDING_MAGIC.to_vec();
+        recording.extend_from_slice(&protocol::RECORDING_VERSION.to_le_bytes());
+        recording.extend_from_slice(&crate::PROTOCOL_VERSION.to_le_bytes());
+        recording.extend_from_slice(&welcome);
+        recording.push(0);
+        records.iter().for_each(|record| recording.extend_from_slice(record));
+        Recording::read(&recording[..])
+    }
+
+    #[test]
+    fn read_recording() {
+        let mut events = Vec::new();
+        event(&mut events, QueueType::ThreadContext, &[&3u32.to_le_bytes()], None);
+        event(&mut events, QueueType::ZoneBegin, &[&10i64.to_le_bytes(), &0x10u64.to_le_bytes()], None);
+        event(&mut events, QueueType::ZoneBegin, &[&5i64.to_le_bytes(), &0x10u64.to_le_bytes()], None);
+        event(&mut events, QueueType::SingleStringData, &[], Some("text"));
+        event(&mut events, QueueType::ZoneText, &[], None);
+        event(&mut events, QueueType::ZoneEnd, &[&5i64.to_le_bytes()], None);
+        event(&mut events, QueueType::ZoneEnd, &[&10i64.to_le_bytes()], None);
+        event(&mut events, QueueType::FrameMarkMsg, &[&20i64.to_le_bytes(), &0u64.to_le_bytes()], None);
+        event(&mut events, QueueType::FrameMarkMsg, &[&50i64.to_le_bytes(), &0u64.to_le_bytes()], None);
+        let mut replies = Vec::new();
+        source_location(&mut replies, 0, 0x20, 0x30, 7);
+        event(&mut replies, QueueType::StringData, &[&0x20u64.to_le_bytes()], Some("work"));
+        event(&mut replies, QueueType::StringData, &[&0x30u64.to_le_bytes()], Some("main.rs"));
+        event(&mut replies, QueueType::ThreadName, &[&3u64.to_le_bytes()], Some("main"));
+
+        let recording = read(&[
+            frame(&events),
+            query(ServerQuery::SourceLocation, 0x10),
+            query(ServerQuery::String, 0x20),
+            query(ServerQuery::String, 0x30),
+            query(ServerQuery::ThreadString, 3),
+            frame(&replies),
+        ]).unwrap();
+        assert_eq!(recording.skipped_frames, 0);
+        assert_eq!(recording.source_locations, [SourceLocation {
+            name: None,
+            function: "work".into(),
+            file: "main.rs".into(),
+            line: 7,
+            color: 0x030201,
+        }]);
+        assert_eq!(recording.threads.len(), 1);
+        let thread = &recording.threads[0];
+        assert_eq!((thread.id, thread.name.as_deref()), (3, Some("main")));
+        let zones: Vec<_> = thread.zones.iter().map(|zone| (zone.start, zone.end, zone.parent, zone.text.as_deref())).collect();
+        assert_eq!(zones, [(10, Some(30), None, None), (15, Some(20), Some(0), Some("text"))]);
+        assert_eq!(recording.frames, [Frame { name: None, start: 20, end: 50 }]);
+
+        assert!(Recording::read(&protocol::HANDSHAKE_SHIBBOLETH[..]).is_err());
+        assert!(read(&[vec![2]]).is_err());
+    }
+
+    #[test]
+    fn replies_match_recorded_queries() {
+        let mut events = Vec::new();
+        event(&mut events, QueueType::ThreadContext, &[&3u32.to_le_bytes()], None);
+        event(&mut events, QueueType::ZoneBegin, &[&10i64.to_le_bytes(), &0x10u64.to_le_bytes()], None);
+        event(&mut events, QueueType::ZoneEnd, &[&5i64.to_le_bytes()], None);
+        event(&mut events, QueueType::ZoneBegin, &[&10i64.to_le_bytes(), &0x40u64.to_le_bytes()], None);
+        event(&mut events, QueueType::ZoneEnd, &[&5i64.to_le_bytes()], None);
+        let mut replies = Vec::new();
+        source_location(&mut replies, 0, 0x20, 0x30, 7);
+        event(&mut replies, QueueType::StringData, &[&0x20u64.to_le_bytes()], Some("late"));
+
+        //the first location was never queried, so the reply is for the second one
+        let recording = read(&[
+            frame(&events),
+            query(ServerQuery::SourceLocation, 0x40),
+            query(ServerQuery::String, 0x20),
+            frame(&replies),
+        ]).unwrap();
+        let functions: Vec<_> = recording.source_locations.iter().map(|location| location.function.as_str()).collect();
+        assert_eq!(functions, ["<unknown 0x10>", "late"]);
+    }
+}
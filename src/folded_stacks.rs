@@ -0,0 +1,320 @@
+//! A backend that aggregates the zones into folded stacks for flamegraphs, selected by the
+//! "folded_stacks" feature.
+//!
+//! Each line of the output is a stack of zone names, rooted at the thread name and separated by
+//! semicolons, followed by the time in nanoseconds spent in it:
+//!
+//! ```text
+//! main;update;physics 1520000
+//! ```
+//!
+//! which `inferno-flamegraph` or `flamegraph.pl` turn into a flamegraph.
+//! The stacks are written when `flush_and_wait` is called, the panic hook in `crate::panic` runs
+//! or else at exit, to the path in the `TRACY_FOLDED_STACKS` environment variable or stacks.folded
+//! in the working directory, weighted by the time spent in the zones themselves, or by their
+//! inclusive time if `TRACY_FOLDED_WEIGHT` is `inclusive`. Nothing is recorded after that, and
+//! zones that are still open are not included.
+//!
+//! Frame marks, messages and plots are ignored.
+
+use crate::{
+    backend::{Backend, SourceCallback},
+    output::{lock, Output},
+    Color, SourceLocationData,
+};
+use libc::{c_int, c_void};
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    env,
+    ffi::CStr,
+    fs::File,
+    io::{self, BufWriter, Write},
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicU32, AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+/// How the stacks are weighted.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Weight {
+    /// The time spent in a zone, excluding its child zones; what flamegraphs expect.
+    SelfTime,
+    /// The time spent in a zone, including its child zones.
+    Inclusive,
+}
+
+#[derive(Clone, Copy, Default)]
+struct Time {
+    inclusive: Duration,
+    self_time: Duration,
+}
+
+type Stacks = HashMap<String, Time>;
+
+/// The times of the stacks of each thread that recorded zones.
+static OUTPUT: Output<Stacks> = Output::new("the folded stacks", output_path, write_output, write_at_exit);
+static NEXT_THREAD: AtomicU64 = AtomicU64::new(1);
+static NEXT_ZONE_ID: AtomicU32 = AtomicU32::new(1);
+
+struct OpenZone {
+    id: u32,
+    name: String,
+    start: Instant,
+    children: Duration,
+}
+
+struct ThreadStacks {
+    thread: String,
+    open: Vec<OpenZone>,
+    stacks: Arc<Mutex<Stacks>>,
+}
+
+impl ThreadStacks {
+    fn new() -> Self {
+        let id = NEXT_THREAD.fetch_add(1, Ordering::Relaxed);
+        Self {
+            thread: thread::current().name().map_or_else(|| format!("thread-{}", id), frame_name),
+            open: Vec::new(),
+            stacks: OUTPUT.register(Stacks::new()),
+        }
+    }
+
+    fn begin(&mut self, id: u32, name: String, start: Instant) {
+        self.open.push(OpenZone { id, name, start, children: Duration::ZERO });
+    }
+
+    fn end(&mut self, id: u32, end: Instant) {
+        let i = match self.open.iter().rposition(|zone| zone.id == id) {
+            Some(i) => i,
+            None => return,
+        };
+        let zone = self.open.remove(i);
+        let inclusive = end.saturating_duration_since(zone.start);
+        if let Some(parent) = i.checked_sub(1).map(|i| &mut self.open[i]) {
+            parent.children += inclusive;
+        }
+
+        let mut stack = self.thread.clone();
+        for name in self.open[..i].iter().map(|zone| &zone.name).chain(Some(&zone.name)) {
+            stack.push(';');
+            stack.push_str(name);
+        }
+        let mut stacks = lock(&self.stacks);
+        let time = stacks.entry(stack).or_default();
+        time.inclusive += inclusive;
+        time.self_time += inclusive.saturating_sub(zone.children);
+    }
+}
+
+thread_local! {
+    static THREAD_STACKS: RefCell<ThreadStacks> = RefCell::new(ThreadStacks::new());
+}
+
+fn with_stacks(f: impl FnOnce(&mut ThreadStacks)) {
+    if OUTPUT.is_stopped() {
+        return;
+    }
+    //the stacks are gone while the thread is exiting
+    let _ = THREAD_STACKS.try_with(|stacks| f(&mut stacks.borrow_mut()));
+}
+
+fn merge(into: &mut Stacks, from: &Stacks) {
+    for (stack, time) in from {
+        let total = into.entry(stack.clone()).or_default();
+        total.inclusive += time.inclusive;
+        total.self_time += time.self_time;
+    }
+}
+
+/// Semicolons separate the frames and line breaks the stacks.
+fn frame_name(name: &str) -> String {
+    name.replace(';', ":").replace(['\n', '\r'], " ")
+}
+
+/// The path the stacks are written to by `flush_and_wait` or at exit.
+pub fn output_path() -> PathBuf {
+    env::var_os("TRACY_FOLDED_STACKS").map_or_else(|| PathBuf::from("stacks.folded"), PathBuf::from)
+}
+
+/// The weight the stacks are written with by `flush_and_wait` or at exit.
+pub fn output_weight() -> Weight {
+    match env::var("TRACY_FOLDED_WEIGHT").as_deref() {
+        Ok("inclusive") => Weight::Inclusive,
+        _ => Weight::SelfTime,
+    }
+}
+
+/// Writes the stacks of the zones that ended so far on all threads to `path`, sorted by stack,
+/// without stopping the recording.
+pub fn write(path: impl AsRef<Path>, weight: Weight) -> io::Result<()> {
+    let mut stacks = Stacks::new();
+    for thread in OUTPUT.threads() {
+        merge(&mut stacks, &lock(&thread));
+    }
+    let mut lines: Vec<_> = stacks.iter()
+        .map(|(stack, time)| (stack, match weight {
+            Weight::SelfTime => time.self_time,
+            Weight::Inclusive => time.inclusive,
+        }))
+        .filter(|(_, time)| *time > Duration::ZERO)
+        .collect();
+    lines.sort_unstable();
+
+    let mut file = BufWriter::new(File::create(path)?);
+    for (stack, time) in lines {
+        writeln!(file, "{} {}", stack, time.as_nanos())?;
+    }
+    file.flush()
+}
+
+fn write_output(path: &Path) -> io::Result<()> {
+    write(path, output_weight())
+}
+
+extern "C" fn write_at_exit() {
+    OUTPUT.write_and_stop();
+}
+
+/// A zone of `FoldedStacks`.
+#[derive(Clone, Copy, Debug)]
+pub struct Zone {
+    id: u32,
+    active: bool,
+}
+
+/// Aggregates the zones into folded stacks.
+pub enum FoldedStacks {}
+
+impl Backend for FoldedStacks {
+    type Zone = Zone;
+
+    fn zone_begin(loc: &SourceLocationData<'_>, _callstack_depth: c_int, active: bool) -> Self::Zone {
+        let zone = Zone { id: NEXT_ZONE_ID.fetch_add(1, Ordering::Relaxed), active };
+        if active {
+            let name = frame_name(&loc.name().unwrap_or_else(|| loc.function()).to_string_lossy());
+            with_stacks(|stacks| stacks.begin(zone.id, name, Instant::now()));
+        }
+        zone
+    }
+
+    fn zone_end(zone: Self::Zone) {
+        if zone.active {
+            let end = Instant::now();
+            with_stacks(|stacks| stacks.end(zone.id, end));
+        }
+    }
+
+    #[inline]
+    fn zone_is_active(zone: Self::Zone) -> bool {
+        zone.active
+    }
+
+    #[inline]
+    fn zone_text(_zone: Self::Zone, _text: &str) {}
+
+    #[inline]
+    fn zone_color(_zone: Self::Zone, _color: Color) {}
+
+    #[inline]
+    fn zone_value(_zone: Self::Zone, _value: u64) {}
+
+    #[inline]
+    fn frame_mark(_name: Option<&'static CStr>) {}
+
+    #[inline]
+    fn frame_mark_start(_name: &'static CStr) {}
+
+    #[inline]
+    fn frame_mark_end(_name: &'static CStr) {}
+
+    #[inline]
+    fn message(_text: &str, _color: Option<Color>, _callstack_depth: c_int) {}
+
+    #[inline]
+    fn app_info(_info: &str) {}
+
+    #[inline]
+    fn plot(_name: &'static CStr, _value: f64) {}
+
+    #[inline]
+    unsafe fn frame_image(_image: *const c_void, _width: u16, _height: u16, _offset: u8, _flip: bool) {}
+
+    #[inline]
+    fn memory_alloc(_ptr: *const u8, _size: usize, _callstack_depth: c_int) {}
+
+    #[inline]
+    fn memory_free(_ptr: *const u8, _callstack_depth: c_int) {}
+
+    #[inline]
+    fn parameter_register(_changed: fn(index: u32, value: i32)) {}
+
+    #[inline]
+    fn parameter_setup(_index: u32, _name: &'static CStr, _is_bool: bool, _value: i32) {}
+
+    #[inline]
+    fn source_callback_register(_callback: &'static SourceCallback) {}
+
+    /// Writes the stacks to `output_path`; they are written at once, so `_timeout` doesn't apply.
+    fn flush(_timeout: Duration) -> bool {
+        OUTPUT.write_and_stop()
+    }
+
+    /// The zones are always recorded.
+    #[inline]
+    fn is_connected() -> bool {
+        true
+    }
+
+    #[inline]
+    fn listen_port() -> Option<u16> {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ms(ms: u64) -> Duration {
+        Duration::from_millis(ms)
+    }
+
+    #[test]
+    fn aggregation() {
+        let mut thread = ThreadStacks { thread: "main".to_string(), open: Vec::new(), stacks: Arc::default() };
+        let t0 = Instant::now();
+        thread.begin(1, "update".to_string(), t0);
+        thread.begin(2, "physics".to_string(), t0 + ms(1));
+        thread.end(2, t0 + ms(4));
+        thread.begin(3, "physics".to_string(), t0 + ms(5));
+        thread.begin(4, "collide".to_string(), t0 + ms(5));
+        thread.end(4, t0 + ms(7));
+        thread.end(3, t0 + ms(8));
+        //unknown zones are ignored
+        thread.end(5, t0 + ms(9));
+        thread.end(1, t0 + ms(10));
+        assert!(thread.open.is_empty());
+
+        let stacks = thread.stacks.lock().unwrap();
+        let time = |stack: &str| {
+            let time = stacks[stack];
+            (time.inclusive, time.self_time)
+        };
+        assert_eq!(stacks.len(), 3);
+        assert_eq!(time("main;update"), (ms(10), ms(4)));
+        assert_eq!(time("main;update;physics"), (ms(6), ms(4)));
+        assert_eq!(time("main;update;physics;collide"), (ms(2), ms(2)));
+    }
+
+    #[test]
+    fn frame_names() {
+        assert_eq!(frame_name("update"), "update");
+        assert_eq!(frame_name("a;b"), "a:b");
+        assert_eq!(frame_name("line\nbreak\r"), "line break ");
+    }
+}
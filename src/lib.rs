@@ -1,41 +1,44 @@
-#![feature(const_cstr_unchecked, const_str_as_bytes, manually_drop_take)]
+#![feature(const_cstr_unchecked, const_str_as_bytes)]
 #![allow(unused)]
 
-use std::{ffi::CStr, marker::PhantomData, mem::ManuallyDrop};
-
-pub mod sys {
-    use libc::{c_char, c_int, c_void, size_t};
-
-    #[repr(C)]
-    pub struct source_location_data {
-        pub name: *const c_char,
-        pub function: *const c_char,
-        pub file: *const c_char,
-        pub line: u32,
-        pub color: u32,
-    }
-
-    #[repr(C)]
-    pub struct zone_context {
-        pub id: u32,
-        pub active: c_int,
-    }
-
-    #[link(name="tracy")]
-    extern "C" {
-        pub fn ___tracy_emit_zone_begin(srcloc: *const source_location_data, active: c_int) -> zone_context;
-        pub fn ___tracy_emit_zone_begin_callstack(srcloc: *const source_location_data, depth: c_int, active: c_int) -> zone_context;
-        pub fn ___tracy_emit_zone_end(ctx: zone_context);
-        pub fn ___tracy_emit_zone_text(ctx: zone_context, txt: *const c_char, size: size_t);
-        pub fn ___tracy_emit_zone_name(ctx: zone_context, txt: *const c_char, size: size_t); 
+use backend::{Active, Backend};
+use std::{cmp, ffi::CStr, fmt, marker::PhantomData, thread, time::{Duration, Instant}};
+
+pub mod backend;
+#[cfg(feature = "chrome_trace")]
+pub mod chrome_trace;
+#[cfg(feature = "demangle")]
+mod demangle;
+pub mod file;
+#[cfg(feature = "folded_stacks")]
+pub mod folded_stacks;
+pub mod memory;
+#[cfg(any(feature = "chrome_trace", feature = "folded_stacks"))]
+mod output;
+pub mod panic;
+pub mod parameter;
+pub mod protocol;
+#[cfg(not(feature = "no_code_transfer"))]
+pub mod source;
+pub mod sys;
+#[cfg(feature = "test_backend")]
+pub mod testing;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Version {
+    pub major: u32,
+    pub minor: u32,
+    pub patch: u32,
+}
 
-        pub fn ___tracy_emit_frame_mark(name: *const c_char);
-        pub fn ___tracy_emit_frame_mark_start(name : *const c_char);
-        pub fn ___tracy_emit_frame_mark_end(name: *const c_char);
-        pub fn ___tracy_emit_frame_image(image: *const c_void, w: u16, h: u16, offset: u8, flip: c_int);
+impl fmt::Display for Version {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
     }
 }
 
+include!(concat!(env!("OUT_DIR"), "/version.rs"));
+
 //FIXME: figure out if 'a is fine enough, or if we need 'static
 pub struct SourceLocation<'a> {
     pub function: &'a CStr,
@@ -57,722 +60,388 @@ macro_rules! loc {
 }
 
 pub struct ZoneContext<'a> {
-    context: ManuallyDrop<sys::zone_context>,
+    zone: <Active as Backend>::Zone,
+    /// The id the panic hook tracks the zone with.
+    panic_id: u32,
     marker: PhantomData<SourceLocationData<'a>>,
 }
 
-pub type CallstackDepth = libc::c_int;
+/// The number of frames to capture for a callstack, between 1 and `CallstackDepth::MAX`.
+#[cfg(not(feature = "no_callstack"))]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct CallstackDepth(u8);
+
+#[cfg(not(feature = "no_callstack"))]
+impl CallstackDepth {
+    /// The deepest callstack Tracy captures.
+    pub const MAX: CallstackDepth = CallstackDepth(62);
+
+    /// Panics if `depth` is 0 or exceeds `MAX`, which is a compile-time error in constants.
+    #[inline]
+    pub const fn new(depth: u32) -> Self {
+        match Self::try_new(depth) {
+            Some(depth) => depth,
+            None => panic!("callstack depth must be between 1 and 62"),
+        }
+    }
+
+    #[inline]
+    pub const fn try_new(depth: u32) -> Option<Self> {
+        if depth == 0 || depth > Self::MAX.0 as u32 {
+            None
+        } else {
+            Some(CallstackDepth(depth as u8))
+        }
+    }
+
+    #[inline]
+    pub const fn get(self) -> u32 {
+        self.0 as u32
+    }
+}
+
+/// The callstack depth used for zones, messages and memory events that don't specify one, set by
+/// the "callstack" feature or the `TRACY_CALLSTACK` environment variable at build time.
+#[cfg(not(feature = "no_callstack"))]
+pub const DEFAULT_CALLSTACK_DEPTH: Option<CallstackDepth> = match option_env!("TRACY_RS_CALLSTACK_DEPTH") {
+    Some(depth) => Some(parse_callstack_depth(depth)),
+    None => None,
+};
+
+#[cfg(not(feature = "no_callstack"))]
+const fn parse_callstack_depth(depth: &str) -> CallstackDepth {
+    let depth = depth.as_bytes();
+    let mut value = 0;
+    let mut i = 0;
+    while i < depth.len() && value <= CallstackDepth::MAX.0 as u32 {
+        assert!(depth[i].is_ascii_digit(), "callstack depth must be a number");
+        value = value * 10 + (depth[i] - b'0') as u32;
+        i += 1;
+    }
+    CallstackDepth::new(value)
+}
+
+/// `DEFAULT_CALLSTACK_DEPTH` as passed to Tracy, which uses 0 for no callstack.
+#[inline]
+pub(crate) const fn default_callstack_depth() -> libc::c_int {
+    #[cfg(not(feature = "no_callstack"))]
+    if let Some(depth) = DEFAULT_CALLSTACK_DEPTH {
+        return depth.0 as libc::c_int;
+    }
+    0
+}
 
 impl<'a> ZoneContext<'a> {
     #[inline]
     pub fn new(loc: &SourceLocationData<'a>, active: bool) -> Self {
-        Self {
-            context: ManuallyDrop::new(unsafe{ sys::___tracy_emit_zone_begin(&loc.data as *const _, if active { 1 } else { 0 }) }),
-            marker: PhantomData,
-        }
+        Self::begun(Active::zone_begin(loc, default_callstack_depth(), active))
     }
 
+    #[cfg(not(feature = "no_callstack"))]
     #[inline]
     pub fn with_callstack(loc: &SourceLocationData<'a>, depth: CallstackDepth, active: bool) -> Self {
+        Self::begun(Active::zone_begin(loc, depth.0 as libc::c_int, active))
+    }
+
+    #[inline]
+    fn begun(zone: <Active as Backend>::Zone) -> Self {
         Self {
-            context: ManuallyDrop::new(unsafe{ sys::___tracy_emit_zone_begin_callstack(&loc.data as *const _, depth, if active { 1 } else { 0 }) }),
+            zone,
+            panic_id: panic::zone_begun(zone),
             marker: PhantomData,
         }
     }
+
+    /// Like `new`, but only active while a viewer is connected.
+    ///
+    /// Without the "on_demand" feature Tracy would queue the zone until a viewer connects; this
+    /// drops it instead.
+    #[inline]
+    pub fn when_connected(loc: &SourceLocationData<'a>) -> Self {
+        Self::new(loc, is_connected())
+    }
+
+    /// Like `with_callstack`, but only active while a viewer is connected.
+    #[cfg(not(feature = "no_callstack"))]
+    #[inline]
+    pub fn with_callstack_when_connected(loc: &SourceLocationData<'a>, depth: CallstackDepth) -> Self {
+        Self::with_callstack(loc, depth, is_connected())
+    }
+
+    #[inline]
+    pub fn is_active(&self) -> bool {
+        Active::zone_is_active(self.zone)
+    }
+
+    /// Overrides the color of the zone's source location.
+    #[inline]
+    pub fn color(&self, color: Color) {
+        Active::zone_color(self.zone, color);
+    }
+
+    /// Attaches a number to the zone, e.g. an entity id or a byte count.
+    #[inline]
+    pub fn value(&self, value: u64) {
+        Active::zone_value(self.zone, value);
+    }
+
+    /// Attaches text to the zone; it is copied, so it doesn't need to outlive the zone.
+    #[inline]
+    pub fn text(&self, text: &str) {
+        Active::zone_text(self.zone, text);
+    }
 }
 
 impl Drop for ZoneContext<'_> {
     #[inline]
     fn drop(&mut self) {
-        //we could avoid this unsafe by manually copying (or deriving Close, though that doesn't
-        //seem useful outside this specific use-case), but this is obviously safe also.
-        unsafe{ sys::___tracy_emit_zone_end(ManuallyDrop::take(&mut self.context)) };
+        if panic::zone_ended(self.panic_id) {
+            Active::zone_end(self.zone);
+        }
+    }
+}
+
+/// Whether a Tracy viewer is currently connected.
+#[inline]
+pub fn is_connected() -> bool {
+    Active::is_connected()
+}
+
+pub(crate) const POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+/// Blocks until a Tracy viewer connects or `timeout` elapses.
+///
+/// Returns whether a viewer is connected.
+pub fn wait_for_connection(timeout: Duration) -> bool {
+    let start = Instant::now();
+    while !is_connected() {
+        let elapsed = start.elapsed();
+        if elapsed >= timeout {
+            return false;
+        }
+        thread::sleep(cmp::min(timeout - elapsed, POLL_INTERVAL));
+    }
+    true
+}
+
+#[inline]
+pub fn message(text: &str) {
+    Active::message(text, None, default_callstack_depth());
+}
+
+#[inline]
+pub fn message_color(text: &str, color: Color) {
+    Active::message(text, Some(color), default_callstack_depth());
+}
+
+/// Sends information about the application, shown with the trace information in the viewer.
+#[inline]
+pub fn app_info(info: &str) {
+    Active::app_info(info);
+}
+
+/// Adds a data point to the plot `name`.
+///
+/// Tracy identifies plots by the address of their name, hence the `'static`.
+#[inline]
+pub fn plot(name: &'static CStr, value: f64) {
+    Active::plot(name, value);
+}
+
+#[doc(hidden)]
+pub fn __report_build_info(name: &str, version: &str, git_hash: Option<&str>, debug_assertions: bool) {
+    app_info(&format!(
+        "{} {}\ngit: {}\ntarget: {}\nprofile: {}\ntracy-rs features: {}",
+        name, version,
+        git_hash.unwrap_or("unknown"),
+        env!("TRACY_RS_TARGET"),
+        if debug_assertions { "debug" } else { "release" },
+        env!("TRACY_RS_FEATURES"),
+    ));
+}
+
+/// Reports the name and version of the calling crate, the git commit, target, profile and the
+/// enabled features of tracy-rs with `app_info`.
+///
+/// The git commit is read from the `GIT_HASH` environment variable at compile time, which a build
+/// script can set with `cargo:rustc-env=GIT_HASH=...`.
+#[macro_export]
+macro_rules! report_build_info {
+    () => ($crate::__report_build_info(
+        env!("CARGO_PKG_NAME"),
+        env!("CARGO_PKG_VERSION"),
+        option_env!("GIT_HASH"),
+        cfg!(debug_assertions),
+    ););
+}
+
+/// The port Tracy listens on for viewers.
+///
+/// That is the port in the `TRACY_PORT` environment variable at startup or the `TRACY_DATA_PORT`
+/// environment variable at build time, or otherwise the first free port starting at 8086.
+/// Returns `None` until Tracy is listening, which happens shortly after startup on its own thread,
+/// and with backends other than Tracy.
+///
+/// Tracy doesn't expose its listening socket, so this looks for a listening TCP socket of this
+/// process in the range of ports Tracy tries and remembers the first one it finds. If the
+/// application listens on a port in that range itself, that port may be returned instead.
+#[inline]
+pub fn listen_port() -> Option<u16> {
+    Active::listen_port()
+}
+
+/// Stops the profiler and waits at most `timeout` for it to send all queued events to the
+/// connected viewer, or for the active backend to write them.
+///
+/// This is the runtime counterpart of the "no_exit" feature. No further events are recorded
+/// afterwards, so only call this when the program is about to exit.
+/// Returns immediately if no viewer is connected to Tracy.
+/// Returns whether all events were sent.
+#[inline]
+pub fn flush_and_wait(timeout: Duration) -> bool {
+    Active::flush(timeout)
+}
+
+/// Calls `flush_and_wait` when dropped.
+///
+/// Keep it alive in `main` to have short-lived programs wait for the viewer without building
+/// with "no_exit", e.g. only when requested by a command-line flag:
+///
+/// ```no_run
+/// # let wait_for_profiler = true;
+/// let _flush = if wait_for_profiler {
+///     Some(tracy_rs::flush_and_wait_on_exit(std::time::Duration::from_secs(10)))
+/// } else {
+///     None
+/// };
+/// ```
+#[must_use = "the events are flushed when this is dropped"]
+pub struct FlushOnExit {
+    timeout: Duration,
+}
+
+impl Drop for FlushOnExit {
+    fn drop(&mut self) {
+        flush_and_wait(self.timeout);
     }
 }
 
-pub type ColorType = u32;
+#[inline]
+pub fn flush_and_wait_on_exit(timeout: Duration) -> FlushOnExit {
+    FlushOnExit { timeout }
+}
+
+/// A color in 0xRRGGBB format.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[repr(transparent)]
+pub struct Color(u32);
+
+impl Color {
+    /// Lets Tracy choose the color.
+    ///
+    /// Since this is 0, Tracy treats `colors::Black` the same way.
+    pub const DEFAULT: Color = Color(0);
+
+    #[inline]
+    pub const fn from_rgb(r: u8, g: u8, b: u8) -> Self {
+        Color((r as u32) << 16 | (g as u32) << 8 | b as u32)
+    }
+
+    /// Ignores the upper 8 bits.
+    #[inline]
+    pub const fn from_u32(rgb: u32) -> Self {
+        Color(rgb & 0xffffff)
+    }
+
+    /// `hue` is in degrees; `saturation` and `value` range from 0 to 255.
+    pub const fn from_hsv(hue: u16, saturation: u8, value: u8) -> Self {
+        let hue = (hue % 360) as u32;
+        let chroma = value as u32 * saturation as u32 / 255;
+        let slope = hue % 120;
+        let x = chroma * (60 - slope.abs_diff(60)) / 60;
+        let (r, g, b) = match hue / 60 {
+            0 => (chroma, x, 0),
+            1 => (x, chroma, 0),
+            2 => (0, chroma, x),
+            3 => (0, x, chroma),
+            4 => (x, 0, chroma),
+            _ => (chroma, 0, x),
+        };
+        let m = value as u32 - chroma;
+        Color((r + m) << 16 | (g + m) << 8 | (b + m))
+    }
+
+    /// Parses "RRGGBB" or "#RRGGBB".
+    ///
+    /// Panics if `hex` is not in either format, which is a compile-time error in constants.
+    pub const fn from_hex_str(hex: &str) -> Self {
+        let hex = hex.as_bytes();
+        let start = if !hex.is_empty() && hex[0] == b'#' { 1 } else { 0 };
+        if hex.len() - start != 6 {
+            panic!("hex color must have 6 digits");
+        }
+        let mut rgb = 0;
+        let mut i = start;
+        while i < hex.len() {
+            let digit = match hex[i] {
+                b'0'..=b'9' => hex[i] - b'0',
+                b'a'..=b'f' => hex[i] - b'a' + 10,
+                b'A'..=b'F' => hex[i] - b'A' + 10,
+                _ => panic!("invalid hex digit in color"),
+            };
+            rgb = rgb << 4 | digit as u32;
+            i += 1;
+        }
+        Color(rgb)
+    }
+
+    /// Derives a stable color for the zone `name` in the module `module_path`.
+    ///
+    /// The hue is picked from 24 well separated ones by the parent module, so zones in the same
+    /// module tree get similar colors; the module itself and the zone name shift it by up to 30
+    /// degrees and vary saturation and brightness, which stay readable on Tracy's dark background.
+    pub const fn auto(module_path: &str, name: &str) -> Self {
+        let path = module_path.as_bytes();
+        let mut parent_end = path.len();
+        while parent_end >= 2 && !(path[parent_end - 1] == b':' && path[parent_end - 2] == b':') {
+            parent_end -= 1;
+        }
+        let parent_end = if parent_end < 2 { path.len() } else { parent_end - 2 };
 
-//TODO: auto-generate via build-script
+        let base = fnv1a(path, 0, parent_end, FNV_OFFSET_BASIS);
+        let leaf = fnv1a(path, parent_end, path.len(), FNV_OFFSET_BASIS);
+        let leaf = fnv1a(name.as_bytes(), 0, name.len(), leaf);
+
+        let hue = ((base % 24) * 15 + 360 + leaf % 61 - 30) % 360;
+        let saturation = 170 + (leaf >> 8) % 50;
+        let value = 200 + (leaf >> 16) % 40;
+        Self::from_hsv(hue as u16, saturation as u8, value as u8)
+    }
+
+    #[inline]
+    pub const fn to_u32(self) -> u32 {
+        self.0
+    }
+}
+
+const FNV_OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+
+const fn fnv1a(bytes: &[u8], start: usize, end: usize, mut hash: u64) -> u64 {
+    let mut i = start;
+    while i < end {
+        hash ^= bytes[i] as u64;
+        hash = hash.wrapping_mul(0x100000001b3);
+        i += 1;
+    }
+    hash
+}
+
+/// The X11 named colors, generated from data/rgb.txt.
 #[allow(non_upper_case_globals, clippy::unreadable_literal)]
-mod colors {
-    use super::ColorType;
-
-    const Snow:                 ColorType = 0xfffafa;
-    const GhostWhite:           ColorType = 0xf8f8ff;
-    const WhiteSmoke:           ColorType = 0xf5f5f5;
-    const Gainsboro:            ColorType = 0xdcdcdc;
-    const FloralWhite:          ColorType = 0xfffaf0;
-    const OldLace:              ColorType = 0xfdf5e6;
-    const Linen:                ColorType = 0xfaf0e6;
-    const AntiqueWhite:         ColorType = 0xfaebd7;
-    const PapayaWhip:           ColorType = 0xffefd5;
-    const BlanchedAlmond:       ColorType = 0xffebcd;
-    const Bisque:               ColorType = 0xffe4c4;
-    const PeachPuff:            ColorType = 0xffdab9;
-    const NavajoWhite:          ColorType = 0xffdead;
-    const Moccasin:             ColorType = 0xffe4b5;
-    const Cornsilk:             ColorType = 0xfff8dc;
-    const Ivory:                ColorType = 0xfffff0;
-    const LemonChiffon:         ColorType = 0xfffacd;
-    const Seashell:             ColorType = 0xfff5ee;
-    const Honeydew:             ColorType = 0xf0fff0;
-    const MintCream:            ColorType = 0xf5fffa;
-    const Azure:                ColorType = 0xf0ffff;
-    const AliceBlue:            ColorType = 0xf0f8ff;
-    const Lavender:             ColorType = 0xe6e6fa;
-    const LavenderBlush:        ColorType = 0xfff0f5;
-    const MistyRose:            ColorType = 0xffe4e1;
-    const White:                ColorType = 0xffffff;
-    const Black:                ColorType = 0x000000;
-    const DarkSlateGray:        ColorType = 0x2f4f4f;
-    const DarkSlateGrey:        ColorType = 0x2f4f4f;
-    const DimGray:              ColorType = 0x696969;
-    const DimGrey:              ColorType = 0x696969;
-    const SlateGray:            ColorType = 0x708090;
-    const SlateGrey:            ColorType = 0x708090;
-    const LightSlateGray:       ColorType = 0x778899;
-    const LightSlateGrey:       ColorType = 0x778899;
-    const Gray:                 ColorType = 0xbebebe;
-    const Grey:                 ColorType = 0xbebebe;
-    const X11Gray:              ColorType = 0xbebebe;
-    const X11Grey:              ColorType = 0xbebebe;
-    const WebGray:              ColorType = 0x808080;
-    const WebGrey:              ColorType = 0x808080;
-    const LightGrey:            ColorType = 0xd3d3d3;
-    const LightGray:            ColorType = 0xd3d3d3;
-    const MidnightBlue:         ColorType = 0x191970;
-    const Navy:                 ColorType = 0x000080;
-    const NavyBlue:             ColorType = 0x000080;
-    const CornflowerBlue:       ColorType = 0x6495ed;
-    const DarkSlateBlue:        ColorType = 0x483d8b;
-    const SlateBlue:            ColorType = 0x6a5acd;
-    const MediumSlateBlue:      ColorType = 0x7b68ee;
-    const LightSlateBlue:       ColorType = 0x8470ff;
-    const MediumBlue:           ColorType = 0x0000cd;
-    const RoyalBlue:            ColorType = 0x4169e1;
-    const Blue:                 ColorType = 0x0000ff;
-    const DodgerBlue:           ColorType = 0x1e90ff;
-    const DeepSkyBlue:          ColorType = 0x00bfff;
-    const SkyBlue:              ColorType = 0x87ceeb;
-    const LightSkyBlue:         ColorType = 0x87cefa;
-    const SteelBlue:            ColorType = 0x4682b4;
-    const LightSteelBlue:       ColorType = 0xb0c4de;
-    const LightBlue:            ColorType = 0xadd8e6;
-    const PowderBlue:           ColorType = 0xb0e0e6;
-    const PaleTurquoise:        ColorType = 0xafeeee;
-    const DarkTurquoise:        ColorType = 0x00ced1;
-    const MediumTurquoise:      ColorType = 0x48d1cc;
-    const Turquoise:            ColorType = 0x40e0d0;
-    const Cyan:                 ColorType = 0x00ffff;
-    const Aqua:                 ColorType = 0x00ffff;
-    const LightCyan:            ColorType = 0xe0ffff;
-    const CadetBlue:            ColorType = 0x5f9ea0;
-    const MediumAquamarine:     ColorType = 0x66cdaa;
-    const Aquamarine:           ColorType = 0x7fffd4;
-    const DarkGreen:            ColorType = 0x006400;
-    const DarkOliveGreen:       ColorType = 0x556b2f;
-    const DarkSeaGreen:         ColorType = 0x8fbc8f;
-    const SeaGreen:             ColorType = 0x2e8b57;
-    const MediumSeaGreen:       ColorType = 0x3cb371;
-    const LightSeaGreen:        ColorType = 0x20b2aa;
-    const PaleGreen:            ColorType = 0x98fb98;
-    const SpringGreen:          ColorType = 0x00ff7f;
-    const LawnGreen:            ColorType = 0x7cfc00;
-    const Green:                ColorType = 0x00ff00;
-    const Lime:                 ColorType = 0x00ff00;
-    const X11Green:             ColorType = 0x00ff00;
-    const WebGreen:             ColorType = 0x008000;
-    const Chartreuse:           ColorType = 0x7fff00;
-    const MediumSpringGreen:    ColorType = 0x00fa9a;
-    const GreenYellow:          ColorType = 0xadff2f;
-    const LimeGreen:            ColorType = 0x32cd32;
-    const YellowGreen:          ColorType = 0x9acd32;
-    const ForestGreen:          ColorType = 0x228b22;
-    const OliveDrab:            ColorType = 0x6b8e23;
-    const DarkKhaki:            ColorType = 0xbdb76b;
-    const Khaki:                ColorType = 0xf0e68c;
-    const PaleGoldenrod:        ColorType = 0xeee8aa;
-    const LightGoldenrodYellow: ColorType = 0xfafad2;
-    const LightYellow:          ColorType = 0xffffe0;
-    const Yellow:               ColorType = 0xffff00;
-    const Gold:                 ColorType = 0xffd700;
-    const LightGoldenrod:       ColorType = 0xeedd82;
-    const Goldenrod:            ColorType = 0xdaa520;
-    const DarkGoldenrod:        ColorType = 0xb8860b;
-    const RosyBrown:            ColorType = 0xbc8f8f;
-    const IndianRed:            ColorType = 0xcd5c5c;
-    const SaddleBrown:          ColorType = 0x8b4513;
-    const Sienna:               ColorType = 0xa0522d;
-    const Peru:                 ColorType = 0xcd853f;
-    const Burlywood:            ColorType = 0xdeb887;
-    const Beige:                ColorType = 0xf5f5dc;
-    const Wheat:                ColorType = 0xf5deb3;
-    const SandyBrown:           ColorType = 0xf4a460;
-    const Tan:                  ColorType = 0xd2b48c;
-    const Chocolate:            ColorType = 0xd2691e;
-    const Firebrick:            ColorType = 0xb22222;
-    const Brown:                ColorType = 0xa52a2a;
-    const DarkSalmon:           ColorType = 0xe9967a;
-    const Salmon:               ColorType = 0xfa8072;
-    const LightSalmon:          ColorType = 0xffa07a;
-    const Orange:               ColorType = 0xffa500;
-    const DarkOrange:           ColorType = 0xff8c00;
-    const Coral:                ColorType = 0xff7f50;
-    const LightCoral:           ColorType = 0xf08080;
-    const Tomato:               ColorType = 0xff6347;
-    const OrangeRed:            ColorType = 0xff4500;
-    const Red:                  ColorType = 0xff0000;
-    const HotPink:              ColorType = 0xff69b4;
-    const DeepPink:             ColorType = 0xff1493;
-    const Pink:                 ColorType = 0xffc0cb;
-    const LightPink:            ColorType = 0xffb6c1;
-    const PaleVioletRed:        ColorType = 0xdb7093;
-    const Maroon:               ColorType = 0xb03060;
-    const X11Maroon:            ColorType = 0xb03060;
-    const WebMaroon:            ColorType = 0x800000;
-    const MediumVioletRed:      ColorType = 0xc71585;
-    const VioletRed:            ColorType = 0xd02090;
-    const Magenta:              ColorType = 0xff00ff;
-    const Fuchsia:              ColorType = 0xff00ff;
-    const Violet:               ColorType = 0xee82ee;
-    const Plum:                 ColorType = 0xdda0dd;
-    const Orchid:               ColorType = 0xda70d6;
-    const MediumOrchid:         ColorType = 0xba55d3;
-    const DarkOrchid:           ColorType = 0x9932cc;
-    const DarkViolet:           ColorType = 0x9400d3;
-    const BlueViolet:           ColorType = 0x8a2be2;
-    const Purple:               ColorType = 0xa020f0;
-    const X11Purple:            ColorType = 0xa020f0;
-    const WebPurple:            ColorType = 0x800080;
-    const MediumPurple:         ColorType = 0x9370db;
-    const Thistle:              ColorType = 0xd8bfd8;
-    const Snow1:                ColorType = 0xfffafa;
-    const Snow2:                ColorType = 0xeee9e9;
-    const Snow3:                ColorType = 0xcdc9c9;
-    const Snow4:                ColorType = 0x8b8989;
-    const Seashell1:            ColorType = 0xfff5ee;
-    const Seashell2:            ColorType = 0xeee5de;
-    const Seashell3:            ColorType = 0xcdc5bf;
-    const Seashell4:            ColorType = 0x8b8682;
-    const AntiqueWhite1:        ColorType = 0xffefdb;
-    const AntiqueWhite2:        ColorType = 0xeedfcc;
-    const AntiqueWhite3:        ColorType = 0xcdc0b0;
-    const AntiqueWhite4:        ColorType = 0x8b8378;
-    const Bisque1:              ColorType = 0xffe4c4;
-    const Bisque2:              ColorType = 0xeed5b7;
-    const Bisque3:              ColorType = 0xcdb79e;
-    const Bisque4:              ColorType = 0x8b7d6b;
-    const PeachPuff1:           ColorType = 0xffdab9;
-    const PeachPuff2:           ColorType = 0xeecbad;
-    const PeachPuff3:           ColorType = 0xcdaf95;
-    const PeachPuff4:           ColorType = 0x8b7765;
-    const NavajoWhite1:         ColorType = 0xffdead;
-    const NavajoWhite2:         ColorType = 0xeecfa1;
-    const NavajoWhite3:         ColorType = 0xcdb38b;
-    const NavajoWhite4:         ColorType = 0x8b795e;
-    const LemonChiffon1:        ColorType = 0xfffacd;
-    const LemonChiffon2:        ColorType = 0xeee9bf;
-    const LemonChiffon3:        ColorType = 0xcdc9a5;
-    const LemonChiffon4:        ColorType = 0x8b8970;
-    const Cornsilk1:            ColorType = 0xfff8dc;
-    const Cornsilk2:            ColorType = 0xeee8cd;
-    const Cornsilk3:            ColorType = 0xcdc8b1;
-    const Cornsilk4:            ColorType = 0x8b8878;
-    const Ivory1:               ColorType = 0xfffff0;
-    const Ivory2:               ColorType = 0xeeeee0;
-    const Ivory3:               ColorType = 0xcdcdc1;
-    const Ivory4:               ColorType = 0x8b8b83;
-    const Honeydew1:            ColorType = 0xf0fff0;
-    const Honeydew2:            ColorType = 0xe0eee0;
-    const Honeydew3:            ColorType = 0xc1cdc1;
-    const Honeydew4:            ColorType = 0x838b83;
-    const LavenderBlush1:       ColorType = 0xfff0f5;
-    const LavenderBlush2:       ColorType = 0xeee0e5;
-    const LavenderBlush3:       ColorType = 0xcdc1c5;
-    const LavenderBlush4:       ColorType = 0x8b8386;
-    const MistyRose1:           ColorType = 0xffe4e1;
-    const MistyRose2:           ColorType = 0xeed5d2;
-    const MistyRose3:           ColorType = 0xcdb7b5;
-    const MistyRose4:           ColorType = 0x8b7d7b;
-    const Azure1:               ColorType = 0xf0ffff;
-    const Azure2:               ColorType = 0xe0eeee;
-    const Azure3:               ColorType = 0xc1cdcd;
-    const Azure4:               ColorType = 0x838b8b;
-    const SlateBlue1:           ColorType = 0x836fff;
-    const SlateBlue2:           ColorType = 0x7a67ee;
-    const SlateBlue3:           ColorType = 0x6959cd;
-    const SlateBlue4:           ColorType = 0x473c8b;
-    const RoyalBlue1:           ColorType = 0x4876ff;
-    const RoyalBlue2:           ColorType = 0x436eee;
-    const RoyalBlue3:           ColorType = 0x3a5fcd;
-    const RoyalBlue4:           ColorType = 0x27408b;
-    const Blue1:                ColorType = 0x0000ff;
-    const Blue2:                ColorType = 0x0000ee;
-    const Blue3:                ColorType = 0x0000cd;
-    const Blue4:                ColorType = 0x00008b;
-    const DodgerBlue1:          ColorType = 0x1e90ff;
-    const DodgerBlue2:          ColorType = 0x1c86ee;
-    const DodgerBlue3:          ColorType = 0x1874cd;
-    const DodgerBlue4:          ColorType = 0x104e8b;
-    const SteelBlue1:           ColorType = 0x63b8ff;
-    const SteelBlue2:           ColorType = 0x5cacee;
-    const SteelBlue3:           ColorType = 0x4f94cd;
-    const SteelBlue4:           ColorType = 0x36648b;
-    const DeepSkyBlue1:         ColorType = 0x00bfff;
-    const DeepSkyBlue2:         ColorType = 0x00b2ee;
-    const DeepSkyBlue3:         ColorType = 0x009acd;
-    const DeepSkyBlue4:         ColorType = 0x00688b;
-    const SkyBlue1:             ColorType = 0x87ceff;
-    const SkyBlue2:             ColorType = 0x7ec0ee;
-    const SkyBlue3:             ColorType = 0x6ca6cd;
-    const SkyBlue4:             ColorType = 0x4a708b;
-    const LightSkyBlue1:        ColorType = 0xb0e2ff;
-    const LightSkyBlue2:        ColorType = 0xa4d3ee;
-    const LightSkyBlue3:        ColorType = 0x8db6cd;
-    const LightSkyBlue4:        ColorType = 0x607b8b;
-    const SlateGray1:           ColorType = 0xc6e2ff;
-    const SlateGray2:           ColorType = 0xb9d3ee;
-    const SlateGray3:           ColorType = 0x9fb6cd;
-    const SlateGray4:           ColorType = 0x6c7b8b;
-    const LightSteelBlue1:      ColorType = 0xcae1ff;
-    const LightSteelBlue2:      ColorType = 0xbcd2ee;
-    const LightSteelBlue3:      ColorType = 0xa2b5cd;
-    const LightSteelBlue4:      ColorType = 0x6e7b8b;
-    const LightBlue1:           ColorType = 0xbfefff;
-    const LightBlue2:           ColorType = 0xb2dfee;
-    const LightBlue3:           ColorType = 0x9ac0cd;
-    const LightBlue4:           ColorType = 0x68838b;
-    const LightCyan1:           ColorType = 0xe0ffff;
-    const LightCyan2:           ColorType = 0xd1eeee;
-    const LightCyan3:           ColorType = 0xb4cdcd;
-    const LightCyan4:           ColorType = 0x7a8b8b;
-    const PaleTurquoise1:       ColorType = 0xbbffff;
-    const PaleTurquoise2:       ColorType = 0xaeeeee;
-    const PaleTurquoise3:       ColorType = 0x96cdcd;
-    const PaleTurquoise4:       ColorType = 0x668b8b;
-    const CadetBlue1:           ColorType = 0x98f5ff;
-    const CadetBlue2:           ColorType = 0x8ee5ee;
-    const CadetBlue3:           ColorType = 0x7ac5cd;
-    const CadetBlue4:           ColorType = 0x53868b;
-    const Turquoise1:           ColorType = 0x00f5ff;
-    const Turquoise2:           ColorType = 0x00e5ee;
-    const Turquoise3:           ColorType = 0x00c5cd;
-    const Turquoise4:           ColorType = 0x00868b;
-    const Cyan1:                ColorType = 0x00ffff;
-    const Cyan2:                ColorType = 0x00eeee;
-    const Cyan3:                ColorType = 0x00cdcd;
-    const Cyan4:                ColorType = 0x008b8b;
-    const DarkSlateGray1:       ColorType = 0x97ffff;
-    const DarkSlateGray2:       ColorType = 0x8deeee;
-    const DarkSlateGray3:       ColorType = 0x79cdcd;
-    const DarkSlateGray4:       ColorType = 0x528b8b;
-    const Aquamarine1:          ColorType = 0x7fffd4;
-    const Aquamarine2:          ColorType = 0x76eec6;
-    const Aquamarine3:          ColorType = 0x66cdaa;
-    const Aquamarine4:          ColorType = 0x458b74;
-    const DarkSeaGreen1:        ColorType = 0xc1ffc1;
-    const DarkSeaGreen2:        ColorType = 0xb4eeb4;
-    const DarkSeaGreen3:        ColorType = 0x9bcd9b;
-    const DarkSeaGreen4:        ColorType = 0x698b69;
-    const SeaGreen1:            ColorType = 0x54ff9f;
-    const SeaGreen2:            ColorType = 0x4eee94;
-    const SeaGreen3:            ColorType = 0x43cd80;
-    const SeaGreen4:            ColorType = 0x2e8b57;
-    const PaleGreen1:           ColorType = 0x9aff9a;
-    const PaleGreen2:           ColorType = 0x90ee90;
-    const PaleGreen3:           ColorType = 0x7ccd7c;
-    const PaleGreen4:           ColorType = 0x548b54;
-    const SpringGreen1:         ColorType = 0x00ff7f;
-    const SpringGreen2:         ColorType = 0x00ee76;
-    const SpringGreen3:         ColorType = 0x00cd66;
-    const SpringGreen4:         ColorType = 0x008b45;
-    const Green1:               ColorType = 0x00ff00;
-    const Green2:               ColorType = 0x00ee00;
-    const Green3:               ColorType = 0x00cd00;
-    const Green4:               ColorType = 0x008b00;
-    const Chartreuse1:          ColorType = 0x7fff00;
-    const Chartreuse2:          ColorType = 0x76ee00;
-    const Chartreuse3:          ColorType = 0x66cd00;
-    const Chartreuse4:          ColorType = 0x458b00;
-    const OliveDrab1:           ColorType = 0xc0ff3e;
-    const OliveDrab2:           ColorType = 0xb3ee3a;
-    const OliveDrab3:           ColorType = 0x9acd32;
-    const OliveDrab4:           ColorType = 0x698b22;
-    const DarkOliveGreen1:      ColorType = 0xcaff70;
-    const DarkOliveGreen2:      ColorType = 0xbcee68;
-    const DarkOliveGreen3:      ColorType = 0xa2cd5a;
-    const DarkOliveGreen4:      ColorType = 0x6e8b3d;
-    const Khaki1:               ColorType = 0xfff68f;
-    const Khaki2:               ColorType = 0xeee685;
-    const Khaki3:               ColorType = 0xcdc673;
-    const Khaki4:               ColorType = 0x8b864e;
-    const LightGoldenrod1:      ColorType = 0xffec8b;
-    const LightGoldenrod2:      ColorType = 0xeedc82;
-    const LightGoldenrod3:      ColorType = 0xcdbe70;
-    const LightGoldenrod4:      ColorType = 0x8b814c;
-    const LightYellow1:         ColorType = 0xffffe0;
-    const LightYellow2:         ColorType = 0xeeeed1;
-    const LightYellow3:         ColorType = 0xcdcdb4;
-    const LightYellow4:         ColorType = 0x8b8b7a;
-    const Yellow1:              ColorType = 0xffff00;
-    const Yellow2:              ColorType = 0xeeee00;
-    const Yellow3:              ColorType = 0xcdcd00;
-    const Yellow4:              ColorType = 0x8b8b00;
-    const Gold1:                ColorType = 0xffd700;
-    const Gold2:                ColorType = 0xeec900;
-    const Gold3:                ColorType = 0xcdad00;
-    const Gold4:                ColorType = 0x8b7500;
-    const Goldenrod1:           ColorType = 0xffc125;
-    const Goldenrod2:           ColorType = 0xeeb422;
-    const Goldenrod3:           ColorType = 0xcd9b1d;
-    const Goldenrod4:           ColorType = 0x8b6914;
-    const DarkGoldenrod1:       ColorType = 0xffb90f;
-    const DarkGoldenrod2:       ColorType = 0xeead0e;
-    const DarkGoldenrod3:       ColorType = 0xcd950c;
-    const DarkGoldenrod4:       ColorType = 0x8b6508;
-    const RosyBrown1:           ColorType = 0xffc1c1;
-    const RosyBrown2:           ColorType = 0xeeb4b4;
-    const RosyBrown3:           ColorType = 0xcd9b9b;
-    const RosyBrown4:           ColorType = 0x8b6969;
-    const IndianRed1:           ColorType = 0xff6a6a;
-    const IndianRed2:           ColorType = 0xee6363;
-    const IndianRed3:           ColorType = 0xcd5555;
-    const IndianRed4:           ColorType = 0x8b3a3a;
-    const Sienna1:              ColorType = 0xff8247;
-    const Sienna2:              ColorType = 0xee7942;
-    const Sienna3:              ColorType = 0xcd6839;
-    const Sienna4:              ColorType = 0x8b4726;
-    const Burlywood1:           ColorType = 0xffd39b;
-    const Burlywood2:           ColorType = 0xeec591;
-    const Burlywood3:           ColorType = 0xcdaa7d;
-    const Burlywood4:           ColorType = 0x8b7355;
-    const Wheat1:               ColorType = 0xffe7ba;
-    const Wheat2:               ColorType = 0xeed8ae;
-    const Wheat3:               ColorType = 0xcdba96;
-    const Wheat4:               ColorType = 0x8b7e66;
-    const Tan1:                 ColorType = 0xffa54f;
-    const Tan2:                 ColorType = 0xee9a49;
-    const Tan3:                 ColorType = 0xcd853f;
-    const Tan4:                 ColorType = 0x8b5a2b;
-    const Chocolate1:           ColorType = 0xff7f24;
-    const Chocolate2:           ColorType = 0xee7621;
-    const Chocolate3:           ColorType = 0xcd661d;
-    const Chocolate4:           ColorType = 0x8b4513;
-    const Firebrick1:           ColorType = 0xff3030;
-    const Firebrick2:           ColorType = 0xee2c2c;
-    const Firebrick3:           ColorType = 0xcd2626;
-    const Firebrick4:           ColorType = 0x8b1a1a;
-    const Brown1:               ColorType = 0xff4040;
-    const Brown2:               ColorType = 0xee3b3b;
-    const Brown3:               ColorType = 0xcd3333;
-    const Brown4:               ColorType = 0x8b2323;
-    const Salmon1:              ColorType = 0xff8c69;
-    const Salmon2:              ColorType = 0xee8262;
-    const Salmon3:              ColorType = 0xcd7054;
-    const Salmon4:              ColorType = 0x8b4c39;
-    const LightSalmon1:         ColorType = 0xffa07a;
-    const LightSalmon2:         ColorType = 0xee9572;
-    const LightSalmon3:         ColorType = 0xcd8162;
-    const LightSalmon4:         ColorType = 0x8b5742;
-    const Orange1:              ColorType = 0xffa500;
-    const Orange2:              ColorType = 0xee9a00;
-    const Orange3:              ColorType = 0xcd8500;
-    const Orange4:              ColorType = 0x8b5a00;
-    const DarkOrange1:          ColorType = 0xff7f00;
-    const DarkOrange2:          ColorType = 0xee7600;
-    const DarkOrange3:          ColorType = 0xcd6600;
-    const DarkOrange4:          ColorType = 0x8b4500;
-    const Coral1:               ColorType = 0xff7256;
-    const Coral2:               ColorType = 0xee6a50;
-    const Coral3:               ColorType = 0xcd5b45;
-    const Coral4:               ColorType = 0x8b3e2f;
-    const Tomato1:              ColorType = 0xff6347;
-    const Tomato2:              ColorType = 0xee5c42;
-    const Tomato3:              ColorType = 0xcd4f39;
-    const Tomato4:              ColorType = 0x8b3626;
-    const OrangeRed1:           ColorType = 0xff4500;
-    const OrangeRed2:           ColorType = 0xee4000;
-    const OrangeRed3:           ColorType = 0xcd3700;
-    const OrangeRed4:           ColorType = 0x8b2500;
-    const Red1:                 ColorType = 0xff0000;
-    const Red2:                 ColorType = 0xee0000;
-    const Red3:                 ColorType = 0xcd0000;
-    const Red4:                 ColorType = 0x8b0000;
-    const DeepPink1:            ColorType = 0xff1493;
-    const DeepPink2:            ColorType = 0xee1289;
-    const DeepPink3:            ColorType = 0xcd1076;
-    const DeepPink4:            ColorType = 0x8b0a50;
-    const HotPink1:             ColorType = 0xff6eb4;
-    const HotPink2:             ColorType = 0xee6aa7;
-    const HotPink3:             ColorType = 0xcd6090;
-    const HotPink4:             ColorType = 0x8b3a62;
-    const Pink1:                ColorType = 0xffb5c5;
-    const Pink2:                ColorType = 0xeea9b8;
-    const Pink3:                ColorType = 0xcd919e;
-    const Pink4:                ColorType = 0x8b636c;
-    const LightPink1:           ColorType = 0xffaeb9;
-    const LightPink2:           ColorType = 0xeea2ad;
-    const LightPink3:           ColorType = 0xcd8c95;
-    const LightPink4:           ColorType = 0x8b5f65;
-    const PaleVioletRed1:       ColorType = 0xff82ab;
-    const PaleVioletRed2:       ColorType = 0xee799f;
-    const PaleVioletRed3:       ColorType = 0xcd6889;
-    const PaleVioletRed4:       ColorType = 0x8b475d;
-    const Maroon1:              ColorType = 0xff34b3;
-    const Maroon2:              ColorType = 0xee30a7;
-    const Maroon3:              ColorType = 0xcd2990;
-    const Maroon4:              ColorType = 0x8b1c62;
-    const VioletRed1:           ColorType = 0xff3e96;
-    const VioletRed2:           ColorType = 0xee3a8c;
-    const VioletRed3:           ColorType = 0xcd3278;
-    const VioletRed4:           ColorType = 0x8b2252;
-    const Magenta1:             ColorType = 0xff00ff;
-    const Magenta2:             ColorType = 0xee00ee;
-    const Magenta3:             ColorType = 0xcd00cd;
-    const Magenta4:             ColorType = 0x8b008b;
-    const Orchid1:              ColorType = 0xff83fa;
-    const Orchid2:              ColorType = 0xee7ae9;
-    const Orchid3:              ColorType = 0xcd69c9;
-    const Orchid4:              ColorType = 0x8b4789;
-    const Plum1:                ColorType = 0xffbbff;
-    const Plum2:                ColorType = 0xeeaeee;
-    const Plum3:                ColorType = 0xcd96cd;
-    const Plum4:                ColorType = 0x8b668b;
-    const MediumOrchid1:        ColorType = 0xe066ff;
-    const MediumOrchid2:        ColorType = 0xd15fee;
-    const MediumOrchid3:        ColorType = 0xb452cd;
-    const MediumOrchid4:        ColorType = 0x7a378b;
-    const DarkOrchid1:          ColorType = 0xbf3eff;
-    const DarkOrchid2:          ColorType = 0xb23aee;
-    const DarkOrchid3:          ColorType = 0x9a32cd;
-    const DarkOrchid4:          ColorType = 0x68228b;
-    const Purple1:              ColorType = 0x9b30ff;
-    const Purple2:              ColorType = 0x912cee;
-    const Purple3:              ColorType = 0x7d26cd;
-    const Purple4:              ColorType = 0x551a8b;
-    const MediumPurple1:        ColorType = 0xab82ff;
-    const MediumPurple2:        ColorType = 0x9f79ee;
-    const MediumPurple3:        ColorType = 0x8968cd;
-    const MediumPurple4:        ColorType = 0x5d478b;
-    const Thistle1:             ColorType = 0xffe1ff;
-    const Thistle2:             ColorType = 0xeed2ee;
-    const Thistle3:             ColorType = 0xcdb5cd;
-    const Thistle4:             ColorType = 0x8b7b8b;
-    const Gray0:                ColorType = 0x000000;
-    const Grey0:                ColorType = 0x000000;
-    const Gray1:                ColorType = 0x030303;
-    const Grey1:                ColorType = 0x030303;
-    const Gray2:                ColorType = 0x050505;
-    const Grey2:                ColorType = 0x050505;
-    const Gray3:                ColorType = 0x080808;
-    const Grey3:                ColorType = 0x080808;
-    const Gray4:                ColorType = 0x0a0a0a;
-    const Grey4:                ColorType = 0x0a0a0a;
-    const Gray5:                ColorType = 0x0d0d0d;
-    const Grey5:                ColorType = 0x0d0d0d;
-    const Gray6:                ColorType = 0x0f0f0f;
-    const Grey6:                ColorType = 0x0f0f0f;
-    const Gray7:                ColorType = 0x121212;
-    const Grey7:                ColorType = 0x121212;
-    const Gray8:                ColorType = 0x141414;
-    const Grey8:                ColorType = 0x141414;
-    const Gray9:                ColorType = 0x171717;
-    const Grey9:                ColorType = 0x171717;
-    const Gray10:               ColorType = 0x1a1a1a;
-    const Grey10:               ColorType = 0x1a1a1a;
-    const Gray11:               ColorType = 0x1c1c1c;
-    const Grey11:               ColorType = 0x1c1c1c;
-    const Gray12:               ColorType = 0x1f1f1f;
-    const Grey12:               ColorType = 0x1f1f1f;
-    const Gray13:               ColorType = 0x212121;
-    const Grey13:               ColorType = 0x212121;
-    const Gray14:               ColorType = 0x242424;
-    const Grey14:               ColorType = 0x242424;
-    const Gray15:               ColorType = 0x262626;
-    const Grey15:               ColorType = 0x262626;
-    const Gray16:               ColorType = 0x292929;
-    const Grey16:               ColorType = 0x292929;
-    const Gray17:               ColorType = 0x2b2b2b;
-    const Grey17:               ColorType = 0x2b2b2b;
-    const Gray18:               ColorType = 0x2e2e2e;
-    const Grey18:               ColorType = 0x2e2e2e;
-    const Gray19:               ColorType = 0x303030;
-    const Grey19:               ColorType = 0x303030;
-    const Gray20:               ColorType = 0x333333;
-    const Grey20:               ColorType = 0x333333;
-    const Gray21:               ColorType = 0x363636;
-    const Grey21:               ColorType = 0x363636;
-    const Gray22:               ColorType = 0x383838;
-    const Grey22:               ColorType = 0x383838;
-    const Gray23:               ColorType = 0x3b3b3b;
-    const Grey23:               ColorType = 0x3b3b3b;
-    const Gray24:               ColorType = 0x3d3d3d;
-    const Grey24:               ColorType = 0x3d3d3d;
-    const Gray25:               ColorType = 0x404040;
-    const Grey25:               ColorType = 0x404040;
-    const Gray26:               ColorType = 0x424242;
-    const Grey26:               ColorType = 0x424242;
-    const Gray27:               ColorType = 0x454545;
-    const Grey27:               ColorType = 0x454545;
-    const Gray28:               ColorType = 0x474747;
-    const Grey28:               ColorType = 0x474747;
-    const Gray29:               ColorType = 0x4a4a4a;
-    const Grey29:               ColorType = 0x4a4a4a;
-    const Gray30:               ColorType = 0x4d4d4d;
-    const Grey30:               ColorType = 0x4d4d4d;
-    const Gray31:               ColorType = 0x4f4f4f;
-    const Grey31:               ColorType = 0x4f4f4f;
-    const Gray32:               ColorType = 0x525252;
-    const Grey32:               ColorType = 0x525252;
-    const Gray33:               ColorType = 0x545454;
-    const Grey33:               ColorType = 0x545454;
-    const Gray34:               ColorType = 0x575757;
-    const Grey34:               ColorType = 0x575757;
-    const Gray35:               ColorType = 0x595959;
-    const Grey35:               ColorType = 0x595959;
-    const Gray36:               ColorType = 0x5c5c5c;
-    const Grey36:               ColorType = 0x5c5c5c;
-    const Gray37:               ColorType = 0x5e5e5e;
-    const Grey37:               ColorType = 0x5e5e5e;
-    const Gray38:               ColorType = 0x616161;
-    const Grey38:               ColorType = 0x616161;
-    const Gray39:               ColorType = 0x636363;
-    const Grey39:               ColorType = 0x636363;
-    const Gray40:               ColorType = 0x666666;
-    const Grey40:               ColorType = 0x666666;
-    const Gray41:               ColorType = 0x696969;
-    const Grey41:               ColorType = 0x696969;
-    const Gray42:               ColorType = 0x6b6b6b;
-    const Grey42:               ColorType = 0x6b6b6b;
-    const Gray43:               ColorType = 0x6e6e6e;
-    const Grey43:               ColorType = 0x6e6e6e;
-    const Gray44:               ColorType = 0x707070;
-    const Grey44:               ColorType = 0x707070;
-    const Gray45:               ColorType = 0x737373;
-    const Grey45:               ColorType = 0x737373;
-    const Gray46:               ColorType = 0x757575;
-    const Grey46:               ColorType = 0x757575;
-    const Gray47:               ColorType = 0x787878;
-    const Grey47:               ColorType = 0x787878;
-    const Gray48:               ColorType = 0x7a7a7a;
-    const Grey48:               ColorType = 0x7a7a7a;
-    const Gray49:               ColorType = 0x7d7d7d;
-    const Grey49:               ColorType = 0x7d7d7d;
-    const Gray50:               ColorType = 0x7f7f7f;
-    const Grey50:               ColorType = 0x7f7f7f;
-    const Gray51:               ColorType = 0x828282;
-    const Grey51:               ColorType = 0x828282;
-    const Gray52:               ColorType = 0x858585;
-    const Grey52:               ColorType = 0x858585;
-    const Gray53:               ColorType = 0x878787;
-    const Grey53:               ColorType = 0x878787;
-    const Gray54:               ColorType = 0x8a8a8a;
-    const Grey54:               ColorType = 0x8a8a8a;
-    const Gray55:               ColorType = 0x8c8c8c;
-    const Grey55:               ColorType = 0x8c8c8c;
-    const Gray56:               ColorType = 0x8f8f8f;
-    const Grey56:               ColorType = 0x8f8f8f;
-    const Gray57:               ColorType = 0x919191;
-    const Grey57:               ColorType = 0x919191;
-    const Gray58:               ColorType = 0x949494;
-    const Grey58:               ColorType = 0x949494;
-    const Gray59:               ColorType = 0x969696;
-    const Grey59:               ColorType = 0x969696;
-    const Gray60:               ColorType = 0x999999;
-    const Grey60:               ColorType = 0x999999;
-    const Gray61:               ColorType = 0x9c9c9c;
-    const Grey61:               ColorType = 0x9c9c9c;
-    const Gray62:               ColorType = 0x9e9e9e;
-    const Grey62:               ColorType = 0x9e9e9e;
-    const Gray63:               ColorType = 0xa1a1a1;
-    const Grey63:               ColorType = 0xa1a1a1;
-    const Gray64:               ColorType = 0xa3a3a3;
-    const Grey64:               ColorType = 0xa3a3a3;
-    const Gray65:               ColorType = 0xa6a6a6;
-    const Grey65:               ColorType = 0xa6a6a6;
-    const Gray66:               ColorType = 0xa8a8a8;
-    const Grey66:               ColorType = 0xa8a8a8;
-    const Gray67:               ColorType = 0xababab;
-    const Grey67:               ColorType = 0xababab;
-    const Gray68:               ColorType = 0xadadad;
-    const Grey68:               ColorType = 0xadadad;
-    const Gray69:               ColorType = 0xb0b0b0;
-    const Grey69:               ColorType = 0xb0b0b0;
-    const Gray70:               ColorType = 0xb3b3b3;
-    const Grey70:               ColorType = 0xb3b3b3;
-    const Gray71:               ColorType = 0xb5b5b5;
-    const Grey71:               ColorType = 0xb5b5b5;
-    const Gray72:               ColorType = 0xb8b8b8;
-    const Grey72:               ColorType = 0xb8b8b8;
-    const Gray73:               ColorType = 0xbababa;
-    const Grey73:               ColorType = 0xbababa;
-    const Gray74:               ColorType = 0xbdbdbd;
-    const Grey74:               ColorType = 0xbdbdbd;
-    const Gray75:               ColorType = 0xbfbfbf;
-    const Grey75:               ColorType = 0xbfbfbf;
-    const Gray76:               ColorType = 0xc2c2c2;
-    const Grey76:               ColorType = 0xc2c2c2;
-    const Gray77:               ColorType = 0xc4c4c4;
-    const Grey77:               ColorType = 0xc4c4c4;
-    const Gray78:               ColorType = 0xc7c7c7;
-    const Grey78:               ColorType = 0xc7c7c7;
-    const Gray79:               ColorType = 0xc9c9c9;
-    const Grey79:               ColorType = 0xc9c9c9;
-    const Gray80:               ColorType = 0xcccccc;
-    const Grey80:               ColorType = 0xcccccc;
-    const Gray81:               ColorType = 0xcfcfcf;
-    const Grey81:               ColorType = 0xcfcfcf;
-    const Gray82:               ColorType = 0xd1d1d1;
-    const Grey82:               ColorType = 0xd1d1d1;
-    const Gray83:               ColorType = 0xd4d4d4;
-    const Grey83:               ColorType = 0xd4d4d4;
-    const Gray84:               ColorType = 0xd6d6d6;
-    const Grey84:               ColorType = 0xd6d6d6;
-    const Gray85:               ColorType = 0xd9d9d9;
-    const Grey85:               ColorType = 0xd9d9d9;
-    const Gray86:               ColorType = 0xdbdbdb;
-    const Grey86:               ColorType = 0xdbdbdb;
-    const Gray87:               ColorType = 0xdedede;
-    const Grey87:               ColorType = 0xdedede;
-    const Gray88:               ColorType = 0xe0e0e0;
-    const Grey88:               ColorType = 0xe0e0e0;
-    const Gray89:               ColorType = 0xe3e3e3;
-    const Grey89:               ColorType = 0xe3e3e3;
-    const Gray90:               ColorType = 0xe5e5e5;
-    const Grey90:               ColorType = 0xe5e5e5;
-    const Gray91:               ColorType = 0xe8e8e8;
-    const Grey91:               ColorType = 0xe8e8e8;
-    const Gray92:               ColorType = 0xebebeb;
-    const Grey92:               ColorType = 0xebebeb;
-    const Gray93:               ColorType = 0xededed;
-    const Grey93:               ColorType = 0xededed;
-    const Gray94:               ColorType = 0xf0f0f0;
-    const Grey94:               ColorType = 0xf0f0f0;
-    const Gray95:               ColorType = 0xf2f2f2;
-    const Grey95:               ColorType = 0xf2f2f2;
-    const Gray96:               ColorType = 0xf5f5f5;
-    const Grey96:               ColorType = 0xf5f5f5;
-    const Gray97:               ColorType = 0xf7f7f7;
-    const Grey97:               ColorType = 0xf7f7f7;
-    const Gray98:               ColorType = 0xfafafa;
-    const Grey98:               ColorType = 0xfafafa;
-    const Gray99:               ColorType = 0xfcfcfc;
-    const Grey99:               ColorType = 0xfcfcfc;
-    const Gray100:              ColorType = 0xffffff;
-    const Grey100:              ColorType = 0xffffff;
-    const DarkGrey:             ColorType = 0xa9a9a9;
-    const DarkGray:             ColorType = 0xa9a9a9;
-    const DarkBlue:             ColorType = 0x00008b;
-    const DarkCyan:             ColorType = 0x008b8b;
-    const DarkMagenta:          ColorType = 0x8b008b;
-    const DarkRed:              ColorType = 0x8b0000;
-    const LightGreen:           ColorType = 0x90ee90;
-    const Crimson:              ColorType = 0xdc143c;
-    const Indigo:               ColorType = 0x4b0082;
-    const Olive:                ColorType = 0x808000;
-    const RebeccaPurple:        ColorType = 0x663399;
-    const Silver:               ColorType = 0xc0c0c0;
-    const Teal:                 ColorType = 0x008080;
+pub mod colors {
+    use super::Color;
+
+    include!(concat!(env!("OUT_DIR"), "/colors.rs"));
 }
 
 pub struct SourceLocationData<'a> {
@@ -782,28 +451,28 @@ pub struct SourceLocationData<'a> {
 
 impl<'a> SourceLocationData<'a> {
     #[inline]
-    pub const fn with_name_and_color(loc: &SourceLocation<'a>, name: &'a CStr, color: ColorType) -> Self {
+    pub const fn with_name_and_color(loc: &SourceLocation<'a>, name: &'a CStr, color: Color) -> Self {
         Self {
             data: sys::source_location_data {
                 name: name.as_ptr(),
                 function: loc.function.as_ptr(),
                 file: loc.file.as_ptr(),
                 line: loc.line,
-                color,
+                color: color.0,
             },
             marker: PhantomData,
         }
     }
 
     #[inline]
-    pub const fn with_color(loc: &SourceLocation<'a>, color: ColorType) -> Self {
+    pub const fn with_color(loc: &SourceLocation<'a>, color: Color) -> Self {
         Self {
             data: sys::source_location_data {
                 name: std::ptr::null(),
                 function: loc.function.as_ptr(),
                 file: loc.file.as_ptr(),
                 line: loc.line,
-                color,
+                color: color.0,
             },
             marker: PhantomData,
         }
@@ -811,12 +480,41 @@ impl<'a> SourceLocationData<'a> {
 
     #[inline]
     pub const fn with_name(loc: &SourceLocation<'a>, name: &'a CStr) -> Self {
-        Self::with_name_and_color(loc, name, 0)
+        Self::with_name_and_color(loc, name, Color::DEFAULT)
     }
 
     #[inline]
     pub const fn without_name_or_color(loc: &SourceLocation<'a>) -> Self {
-        Self::with_color(loc, 0)
+        Self::with_color(loc, Color::DEFAULT)
+    }
+
+    #[inline]
+    pub fn name(&self) -> Option<&'a CStr> {
+        if self.data.name.is_null() {
+            None
+        } else {
+            Some(unsafe{ CStr::from_ptr(self.data.name) })
+        }
+    }
+
+    #[inline]
+    pub fn function(&self) -> &'a CStr {
+        unsafe{ CStr::from_ptr(self.data.function) }
+    }
+
+    #[inline]
+    pub fn file(&self) -> &'a CStr {
+        unsafe{ CStr::from_ptr(self.data.file) }
+    }
+
+    #[inline]
+    pub fn line(&self) -> u32 {
+        self.data.line
+    }
+
+    #[inline]
+    pub fn color(&self) -> Color {
+        Color(self.data.color)
     }
 }
 
@@ -825,128 +523,486 @@ macro_rules! cstr {
     ( $( $str: expr ),* ) => (unsafe{ ::std::ffi::CStr::from_bytes_with_nul_unchecked(concat!($( $str ),* , "\0").as_bytes()) })
 }
 
-//TODO: empty on !cfg(feature = "enable")
+/// Begins a zone that lasts until the end of the enclosing scope.
+///
+/// `$active` can be left out to make the zone active only while a viewer is connected (see
+/// `ZoneContext::when_connected`).
+/// To access the zone, e.g. to change its color, bind it to a name with `let`:
+///
+/// ```no_run
+/// # use tracy_rs::{colors, zone};
+/// # let cache_hit = true;
+/// zone!(let zone = true);
+/// zone.color(if cache_hit { colors::Green } else { colors::Red });
+/// ```
 #[macro_export]
 macro_rules! zone {
-    ($active: expr) => (let _zone = ZoneContext::new(&{
-        const LOC: $crate::SourceLocationData = $crate::SourceLocationData::without_name_or_color(
-            &$crate::loc!(),
-        );
-        LOC
-    }, $active););
+    (@ $zone: ident; $active: expr) => (
+        let $zone = $crate::ZoneContext::new(&{
+            const LOC: $crate::SourceLocationData = $crate::SourceLocationData::without_name_or_color(
+                &$crate::loc!(),
+            );
+            LOC
+        }, $active);
+    );
+    (@ $zone: ident;) => (
+        let $zone = $crate::ZoneContext::when_connected(&{
+            const LOC: $crate::SourceLocationData = $crate::SourceLocationData::without_name_or_color(
+                &$crate::loc!(),
+            );
+            LOC
+        });
+    );
+    (let $zone: ident = $($args: tt)*) => ($crate::zone!(@ $zone; $($args)*););
+    ($($args: tt)*) => ($crate::zone!(@ _zone; $($args)*););
 }
 
 #[macro_export]
 macro_rules! zone_n {
-    ($name: expr, $active: expr) => (let _zone = $crate::ZoneContext::new(&{
-        const LOC: $crate::SourceLocationData = $crate::SourceLocationData::with_name(
-            &$crate::loc!(),
-            $crate::cstr!($name),
-        );
-        LOC
-    }, $active););
+    (@ $zone: ident; $name: expr, $active: expr) => (
+        let $zone = $crate::ZoneContext::new(&{
+            const LOC: $crate::SourceLocationData = $crate::SourceLocationData::with_name(
+                &$crate::loc!(),
+                $crate::cstr!($name),
+            );
+            LOC
+        }, $active);
+    );
+    (@ $zone: ident; $name: expr) => (
+        let $zone = $crate::ZoneContext::when_connected(&{
+            const LOC: $crate::SourceLocationData = $crate::SourceLocationData::with_name(
+                &$crate::loc!(),
+                $crate::cstr!($name),
+            );
+            LOC
+        });
+    );
+    (let $zone: ident = $($args: tt)*) => ($crate::zone_n!(@ $zone; $($args)*););
+    ($($args: tt)*) => ($crate::zone_n!(@ _zone; $($args)*););
 }
 
 #[macro_export]
 macro_rules! zone_c {
-    ($color: expr, $active: expr) => (let _zone = $crate::ZoneContext::new(&{
-        const LOC: $crate::SourceLocationData = $crate::SourceLocationData::with_color(
-            &$crate::loc!(),
-            $color,
-        );
-        LOC
-    }, $active););
+    (@ $zone: ident; $color: expr, $active: expr) => (
+        let $zone = $crate::ZoneContext::new(&{
+            const LOC: $crate::SourceLocationData = $crate::SourceLocationData::with_color(
+                &$crate::loc!(),
+                $color,
+            );
+            LOC
+        }, $active);
+    );
+    (@ $zone: ident; $color: expr) => (
+        let $zone = $crate::ZoneContext::when_connected(&{
+            const LOC: $crate::SourceLocationData = $crate::SourceLocationData::with_color(
+                &$crate::loc!(),
+                $color,
+            );
+            LOC
+        });
+    );
+    (let $zone: ident = $($args: tt)*) => ($crate::zone_c!(@ $zone; $($args)*););
+    ($($args: tt)*) => ($crate::zone_c!(@ _zone; $($args)*););
 }
 
 #[macro_export]
 macro_rules! zone_nc {
-    ($name: expr, $color: expr, $active: expr) => (let _zone = $crate::ZoneContext::new(&{
-        const LOC: $crate::SourceLocationData = $crate::SourceLocationData::with_name_and_color(
-            &$crate::loc!(),
-            $crate::cstr!($name),
-            $color,
-        );
-        LOC
-    }, $active););
+    (@ $zone: ident; $name: expr, $color: expr, $active: expr) => (
+        let $zone = $crate::ZoneContext::new(&{
+            const LOC: $crate::SourceLocationData = $crate::SourceLocationData::with_name_and_color(
+                &$crate::loc!(),
+                $crate::cstr!($name),
+                $color,
+            );
+            LOC
+        }, $active);
+    );
+    (@ $zone: ident; $name: expr, $color: expr) => (
+        let $zone = $crate::ZoneContext::when_connected(&{
+            const LOC: $crate::SourceLocationData = $crate::SourceLocationData::with_name_and_color(
+                &$crate::loc!(),
+                $crate::cstr!($name),
+                $color,
+            );
+            LOC
+        });
+    );
+    (let $zone: ident = $($args: tt)*) => ($crate::zone_nc!(@ $zone; $($args)*););
+    ($($args: tt)*) => ($crate::zone_nc!(@ _zone; $($args)*););
 }
 
+/// Like `zone!`, but also captures the callstack where the zone begins, `$depth` frames deep.
+///
+/// `$depth` must be a constant `CallstackDepth`; deeper callstacks take longer to capture.
+///
+/// ```no_run
+/// # use tracy_rs::{zone_s, CallstackDepth};
+/// fn load_texture(path: &str) {
+///     //see which callers load textures
+///     zone_s!(CallstackDepth::new(8), true);
+///     # let _ = path;
+/// }
+/// ```
+#[cfg(not(feature = "no_callstack"))]
 #[macro_export]
 macro_rules! zone_s {
-    ($depth: expr, $active: expr) => (let _zone = ZoneContext::with_callstack(&{
-        const LOC: $crate::SourceLocationData = $crate::SourceLocationData::without_name_or_color(
-            &$crate::loc!(),
-        );
-        LOC
-    }, $depth, $active););
+    (@ $zone: ident; $depth: expr, $active: expr) => (
+        let $zone = $crate::ZoneContext::with_callstack(&{
+            const LOC: $crate::SourceLocationData = $crate::SourceLocationData::without_name_or_color(
+                &$crate::loc!(),
+            );
+            LOC
+        }, {
+            const DEPTH: $crate::CallstackDepth = $depth;
+            DEPTH
+        }, $active);
+    );
+    (@ $zone: ident; $depth: expr) => (
+        let $zone = $crate::ZoneContext::with_callstack_when_connected(&{
+            const LOC: $crate::SourceLocationData = $crate::SourceLocationData::without_name_or_color(
+                &$crate::loc!(),
+            );
+            LOC
+        }, {
+            const DEPTH: $crate::CallstackDepth = $depth;
+            DEPTH
+        });
+    );
+    (let $zone: ident = $($args: tt)*) => ($crate::zone_s!(@ $zone; $($args)*););
+    ($($args: tt)*) => ($crate::zone_s!(@ _zone; $($args)*););
 }
 
+#[cfg(not(feature = "no_callstack"))]
 #[macro_export]
 macro_rules! zone_ns {
-    ($name: expr, $depth: expr, $active: expr) => (let _zone = $crate::ZoneContext::with_callstack(&{
-        const LOC: $crate::SourceLocationData = $crate::SourceLocationData::with_name(
-            &$crate::loc!(),
-            $crate::cstr!($name),
-        );
-        LOC
-    }, $depth, $active););
+    (@ $zone: ident; $name: expr, $depth: expr, $active: expr) => (
+        let $zone = $crate::ZoneContext::with_callstack(&{
+            const LOC: $crate::SourceLocationData = $crate::SourceLocationData::with_name(
+                &$crate::loc!(),
+                $crate::cstr!($name),
+            );
+            LOC
+        }, {
+            const DEPTH: $crate::CallstackDepth = $depth;
+            DEPTH
+        }, $active);
+    );
+    (@ $zone: ident; $name: expr, $depth: expr) => (
+        let $zone = $crate::ZoneContext::with_callstack_when_connected(&{
+            const LOC: $crate::SourceLocationData = $crate::SourceLocationData::with_name(
+                &$crate::loc!(),
+                $crate::cstr!($name),
+            );
+            LOC
+        }, {
+            const DEPTH: $crate::CallstackDepth = $depth;
+            DEPTH
+        });
+    );
+    (let $zone: ident = $($args: tt)*) => ($crate::zone_ns!(@ $zone; $($args)*););
+    ($($args: tt)*) => ($crate::zone_ns!(@ _zone; $($args)*););
 }
 
+#[cfg(not(feature = "no_callstack"))]
 #[macro_export]
 macro_rules! zone_cs {
-    ($color: expr, $depth: expr, $active: expr) => (let _zone = $crate::ZoneContext::with_callstack(&{
-        const LOC: $crate::SourceLocationData = $crate::SourceLocationData::with_color(
-            &$crate::loc!(),
-            $color,
-        );
-        LOC
-    }, $depth, $active););
+    (@ $zone: ident; $color: expr, $depth: expr, $active: expr) => (
+        let $zone = $crate::ZoneContext::with_callstack(&{
+            const LOC: $crate::SourceLocationData = $crate::SourceLocationData::with_color(
+                &$crate::loc!(),
+                $color,
+            );
+            LOC
+        }, {
+            const DEPTH: $crate::CallstackDepth = $depth;
+            DEPTH
+        }, $active);
+    );
+    (@ $zone: ident; $color: expr, $depth: expr) => (
+        let $zone = $crate::ZoneContext::with_callstack_when_connected(&{
+            const LOC: $crate::SourceLocationData = $crate::SourceLocationData::with_color(
+                &$crate::loc!(),
+                $color,
+            );
+            LOC
+        }, {
+            const DEPTH: $crate::CallstackDepth = $depth;
+            DEPTH
+        });
+    );
+    (let $zone: ident = $($args: tt)*) => ($crate::zone_cs!(@ $zone; $($args)*););
+    ($($args: tt)*) => ($crate::zone_cs!(@ _zone; $($args)*););
 }
 
+#[cfg(not(feature = "no_callstack"))]
 #[macro_export]
 macro_rules! zone_ncs {
-    ($name: expr, $color: expr, $depth: expr, $active: expr) => (let _zone = $crate::ZoneContext::with_callstack(&{
-        const LOC: $crate::SourceLocationData = $crate::SourceLocationData::with_name_and_color(
-            &$crate::loc!(),
-            $crate::cstr!($name),
-            $color,
-        );
-        LOC
-    }, $depth, $active););
+    (@ $zone: ident; $name: expr, $color: expr, $depth: expr, $active: expr) => (
+        let $zone = $crate::ZoneContext::with_callstack(&{
+            const LOC: $crate::SourceLocationData = $crate::SourceLocationData::with_name_and_color(
+                &$crate::loc!(),
+                $crate::cstr!($name),
+                $color,
+            );
+            LOC
+        }, {
+            const DEPTH: $crate::CallstackDepth = $depth;
+            DEPTH
+        }, $active);
+    );
+    (@ $zone: ident; $name: expr, $color: expr, $depth: expr) => (
+        let $zone = $crate::ZoneContext::with_callstack_when_connected(&{
+            const LOC: $crate::SourceLocationData = $crate::SourceLocationData::with_name_and_color(
+                &$crate::loc!(),
+                $crate::cstr!($name),
+                $color,
+            );
+            LOC
+        }, {
+            const DEPTH: $crate::CallstackDepth = $depth;
+            DEPTH
+        });
+    );
+    (let $zone: ident = $($args: tt)*) => ($crate::zone_ncs!(@ $zone; $($args)*););
+    ($($args: tt)*) => ($crate::zone_ncs!(@ _zone; $($args)*););
+}
+
+/// Like `zone!`, with `value` attached to the zone.
+#[macro_export]
+macro_rules! zone_v {
+    (@ $zone: ident; $value: expr, $active: expr) => (
+        let $zone = $crate::ZoneContext::new(&{
+            const LOC: $crate::SourceLocationData = $crate::SourceLocationData::without_name_or_color(
+                &$crate::loc!(),
+            );
+            LOC
+        }, $active);
+        $zone.value($value);
+    );
+    (@ $zone: ident; $value: expr) => (
+        let $zone = $crate::ZoneContext::when_connected(&{
+            const LOC: $crate::SourceLocationData = $crate::SourceLocationData::without_name_or_color(
+                &$crate::loc!(),
+            );
+            LOC
+        });
+        $zone.value($value);
+    );
+    (let $zone: ident = $($args: tt)*) => ($crate::zone_v!(@ $zone; $($args)*););
+    ($($args: tt)*) => ($crate::zone_v!(@ _zone; $($args)*););
+}
+
+/// Like `zone_n!`, with `value` attached to the zone.
+#[macro_export]
+macro_rules! zone_nv {
+    (@ $zone: ident; $name: expr, $value: expr, $active: expr) => (
+        let $zone = $crate::ZoneContext::new(&{
+            const LOC: $crate::SourceLocationData = $crate::SourceLocationData::with_name(
+                &$crate::loc!(),
+                $crate::cstr!($name),
+            );
+            LOC
+        }, $active);
+        $zone.value($value);
+    );
+    (@ $zone: ident; $name: expr, $value: expr) => (
+        let $zone = $crate::ZoneContext::when_connected(&{
+            const LOC: $crate::SourceLocationData = $crate::SourceLocationData::with_name(
+                &$crate::loc!(),
+                $crate::cstr!($name),
+            );
+            LOC
+        });
+        $zone.value($value);
+    );
+    (let $zone: ident = $($args: tt)*) => ($crate::zone_nv!(@ $zone; $($args)*););
+    ($($args: tt)*) => ($crate::zone_nv!(@ _zone; $($args)*););
+}
+
+/// Like `zone_nc!`, with the color derived from the module path and `name` by `Color::auto`.
+#[macro_export]
+macro_rules! zone_auto {
+    (@ $zone: ident; $name: expr, $active: expr) => (
+        let $zone = $crate::ZoneContext::new(&{
+            const LOC: $crate::SourceLocationData = $crate::SourceLocationData::with_name_and_color(
+                &$crate::loc!(),
+                $crate::cstr!($name),
+                $crate::Color::auto(module_path!(), $name),
+            );
+            LOC
+        }, $active);
+    );
+    (@ $zone: ident; $name: expr) => (
+        let $zone = $crate::ZoneContext::when_connected(&{
+            const LOC: $crate::SourceLocationData = $crate::SourceLocationData::with_name_and_color(
+                &$crate::loc!(),
+                $crate::cstr!($name),
+                $crate::Color::auto(module_path!(), $name),
+            );
+            LOC
+        });
+    );
+    (let $zone: ident = $($args: tt)*) => ($crate::zone_auto!(@ $zone; $($args)*););
+    ($($args: tt)*) => ($crate::zone_auto!(@ _zone; $($args)*););
+}
+
+/// Like `zone_ncs!`, with the color derived from the module path and `name` by `Color::auto`.
+#[cfg(not(feature = "no_callstack"))]
+#[macro_export]
+macro_rules! zone_auto_s {
+    (@ $zone: ident; $name: expr, $depth: expr, $active: expr) => (
+        let $zone = $crate::ZoneContext::with_callstack(&{
+            const LOC: $crate::SourceLocationData = $crate::SourceLocationData::with_name_and_color(
+                &$crate::loc!(),
+                $crate::cstr!($name),
+                $crate::Color::auto(module_path!(), $name),
+            );
+            LOC
+        }, {
+            const DEPTH: $crate::CallstackDepth = $depth;
+            DEPTH
+        }, $active);
+    );
+    (@ $zone: ident; $name: expr, $depth: expr) => (
+        let $zone = $crate::ZoneContext::with_callstack_when_connected(&{
+            const LOC: $crate::SourceLocationData = $crate::SourceLocationData::with_name_and_color(
+                &$crate::loc!(),
+                $crate::cstr!($name),
+                $crate::Color::auto(module_path!(), $name),
+            );
+            LOC
+        }, {
+            const DEPTH: $crate::CallstackDepth = $depth;
+            DEPTH
+        });
+    );
+    (let $zone: ident = $($args: tt)*) => ($crate::zone_auto_s!(@ $zone; $($args)*););
+    ($($args: tt)*) => ($crate::zone_auto_s!(@ _zone; $($args)*););
 }
 
 #[macro_export]
 macro_rules! frame_mark {
-    () => (unsafe { $crate::sys::___tracy_emit_frame_mark(::core::ptr::null()) };);
+    () => (<$crate::backend::Active as $crate::backend::Backend>::frame_mark(::core::option::Option::None););
 }
 
 #[macro_export]
 macro_rules! frame_mark_name {
-    ($name: expr) => (unsafe { $crate::sys::___tracy_emit_frame_mark($crate::cstr!($name)) };);
+    ($name: expr) => (<$crate::backend::Active as $crate::backend::Backend>::frame_mark(::core::option::Option::Some($crate::cstr!($name))););
 }
 
 #[macro_export]
 macro_rules! frame_mark_start {
-    ($name: expr) => (unsafe { $crate::sys::___tracy_emit_frame_mark_start($crate::cstr!($name)) };);
+    ($name: expr) => (<$crate::backend::Active as $crate::backend::Backend>::frame_mark_start($crate::cstr!($name)););
 }
 
 #[macro_export]
 macro_rules! frame_mark_end {
-    ($name: expr) => (unsafe { $crate::sys::___tracy_emit_frame_mark_end($crate::cstr!($name)) };);
+    ($name: expr) => (<$crate::backend::Active as $crate::backend::Backend>::frame_mark_end($crate::cstr!($name)););
 }
 
+/// Sends a screenshot for the frame `$offset` frames before the current one.
+///
+/// `$image` points to `$width` * `$height` RGBA pixels, which are copied; `$width` and `$height`
+/// must be divisible by 4. `$flip` flips the image vertically, e.g. for images read from OpenGL.
+/// None of this is checked.
 //TODO: bounds-checked variant
+#[cfg(not(feature = "no_frame_image"))]
 #[macro_export]
 macro_rules! frame_mark_image_unchecked {
-    ($image: expr, $width: expr, $height: expr, $offset: expr, $flip: expr) => ($crate::sys::___tracy_emit_frame_mark_image($image as *const ::libc::c_void, $width, $height, $offset, if $flip { 1 } else { 0 }););
+    ($image: expr, $width: expr, $height: expr, $offset: expr, $flip: expr) => ({
+        //outside of the unsafe block, so the arguments can't call unsafe code unchecked
+        let (image, width, height, offset, flip) = ($image as *const ::core::ffi::c_void, $width, $height, $offset, $flip);
+        unsafe{ <$crate::backend::Active as $crate::backend::Backend>::frame_image(image, width, height, offset, flip) };
+    };);
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[cfg(not(feature = "no_callstack"))]
     #[test]
     fn it_works() {
-        zone_ncs!("foo", 0, 1, true);
-        ZoneContext::with_callstack(&SourceLocationData::with_name_and_color(&loc!(), cstr!("foo"), 0), 1, true);
+        zone_ncs!("foo", Color::DEFAULT, CallstackDepth::new(1), true);
+        ZoneContext::with_callstack(&SourceLocationData::with_name_and_color(&loc!(), cstr!("foo"), Color::DEFAULT), CallstackDepth::new(1), true);
+    }
+
+    #[test]
+    fn zone_value() {
+        zone_nv!("foo", 42, true);
+        zone_v!(7);
+    }
+
+    #[test]
+    fn zone_color() {
+        zone_n!(let zone = "foo", true);
+        zone.color(colors::Red);
+        zone_auto!(let zone = "bar");
+        zone.color(colors::Green);
+    }
+
+    #[test]
+    fn color_constructors() {
+        assert_eq!(Color::from_rgb(0x1e, 0x90, 0xff), colors::DodgerBlue);
+        assert_eq!(Color::from_hex_str("#1e90ff"), colors::DodgerBlue);
+        assert_eq!(Color::from_hex_str("1E90FF"), colors::DodgerBlue);
+        assert_eq!(Color::from_hsv(0, 255, 255), colors::Red);
+        assert_eq!(Color::from_hsv(240, 255, 255), colors::Blue);
+        assert_eq!(Color::from_hsv(60, 255, 255), colors::Yellow);
+        assert_eq!(Color::from_u32(0xff1e90ff), colors::DodgerBlue);
+    }
+
+    #[test]
+    fn auto_color() {
+        const COLOR: Color = Color::auto("foo::bar", "baz");
+        assert_eq!(COLOR, Color::auto("foo::bar", "baz"));
+        assert_ne!(COLOR, Color::DEFAULT);
+        assert_ne!(COLOR, Color::auto("foo::bar", "qux"));
+    }
+
+    #[cfg(all(feature = "test_backend", not(feature = "no_frame_image")))]
+    #[test]
+    fn frame_image() {
+        let image = [0u8; 8 * 4 * 4];
+        let events = testing::capture(|| {
+            frame_mark_image_unchecked!(image.as_ptr(), 8, 4, 1, true);
+        });
+        assert_eq!(events, [testing::Event::FrameImage { width: 8, height: 4, offset: 1, flip: true }]);
+    }
+
+    #[cfg(feature = "test_backend")]
+    #[test]
+    fn recorded_events() {
+        use testing::Event;
+
+        let events = testing::capture(|| {
+            zone_nc!(let outer = "outer", colors::Red, true);
+            outer.text("text");
+            {
+                zone_nv!("inner", 42, true);
+                zone_n!("inactive", false);
+            }
+            message("message");
+            plot(cstr!("plot"), 1.5);
+            frame_mark!();
+        });
+        assert_eq!(testing::zone_names(&events), ["outer", "inner"]);
+
+        let zone = |i: usize| match &events[i] {
+            Event::ZoneBegin { id, location } => (*id, location.color),
+            event => panic!("expected a zone to begin, got {:?}", event),
+        };
+        let (outer, color) = zone(0);
+        assert_eq!(color, colors::Red);
+        let (inner, _) = zone(2);
+        assert_eq!(events[1], Event::ZoneText { id: outer, text: "text".into() });
+        assert_eq!(events[3..], [
+            Event::ZoneValue { id: inner, value: 42 },
+            Event::ZoneEnd { id: inner },
+            Event::Message { text: "message".into(), color: None },
+            Event::Plot { name: "plot".into(), value: 1.5 },
+            Event::FrameMark { name: None },
+            Event::ZoneEnd { id: outer },
+        ]);
     }
 }
@@ -0,0 +1,116 @@
+//! Memory allocation events.
+
+use crate::{backend::{Active, Backend}, default_callstack_depth};
+use std::alloc::{GlobalAlloc, Layout};
+
+/// Records an allocation of `size` bytes at `ptr`.
+///
+/// Captures a callstack of `DEFAULT_CALLSTACK_DEPTH`, if set.
+#[inline]
+pub fn alloc(ptr: *const u8, size: usize) {
+    Active::memory_alloc(ptr, size, default_callstack_depth());
+}
+
+/// Records that the allocation at `ptr` was freed.
+///
+/// Captures a callstack of `DEFAULT_CALLSTACK_DEPTH`, if set.
+#[inline]
+pub fn free(ptr: *const u8) {
+    Active::memory_free(ptr, default_callstack_depth());
+}
+
+/// Wraps a global allocator to record all allocations.
+///
+/// ```no_run
+/// #[global_allocator]
+/// static ALLOC: tracy_rs::memory::ProfiledAllocator<std::alloc::System> =
+///     tracy_rs::memory::ProfiledAllocator(std::alloc::System);
+/// ```
+pub struct ProfiledAllocator<A>(pub A);
+
+/// Failed allocations are not recorded.
+unsafe impl<A: GlobalAlloc> GlobalAlloc for ProfiledAllocator<A> {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = self.0.alloc(layout);
+        if !ptr.is_null() {
+            alloc(ptr, layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let ptr = self.0.alloc_zeroed(layout);
+        if !ptr.is_null() {
+            alloc(ptr, layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        free(ptr);
+        self.0.dealloc(ptr, layout);
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new_ptr = self.0.realloc(ptr, layout, new_size);
+        //the old allocation is still valid if this fails
+        if !new_ptr.is_null() {
+            free(ptr);
+            alloc(new_ptr, new_size);
+        }
+        new_ptr
+    }
+}
+
+#[cfg(all(test, feature = "test_backend"))]
+mod tests {
+    use super::*;
+    use crate::testing::{self, Event};
+    use std::{alloc::System, ptr};
+
+    struct Exhausted;
+
+    unsafe impl GlobalAlloc for Exhausted {
+        unsafe fn alloc(&self, _layout: Layout) -> *mut u8 {
+            ptr::null_mut()
+        }
+
+        unsafe fn dealloc(&self, _ptr: *mut u8, _layout: Layout) {}
+
+        unsafe fn realloc(&self, _ptr: *mut u8, _layout: Layout, _new_size: usize) -> *mut u8 {
+            ptr::null_mut()
+        }
+    }
+
+    #[test]
+    fn records_allocations() {
+        let layout = Layout::from_size_align(16, 8).unwrap();
+        let allocator = ProfiledAllocator(System);
+        let mut ptrs = Vec::new();
+        let events = testing::capture(|| unsafe {
+            let ptr = allocator.alloc(layout);
+            let new_ptr = allocator.realloc(ptr, layout, 64);
+            allocator.dealloc(new_ptr, Layout::from_size_align(64, 8).unwrap());
+            ptrs.extend([ptr as usize, new_ptr as usize].iter());
+        });
+        assert_eq!(events, [
+            Event::MemoryAlloc { ptr: ptrs[0], size: 16 },
+            Event::MemoryFree { ptr: ptrs[0] },
+            Event::MemoryAlloc { ptr: ptrs[1], size: 64 },
+            Event::MemoryFree { ptr: ptrs[1] },
+        ]);
+    }
+
+    #[test]
+    fn skips_failed_allocations() {
+        let layout = Layout::from_size_align(16, 8).unwrap();
+        let allocator = ProfiledAllocator(Exhausted);
+        let events = testing::capture(|| unsafe {
+            assert!(allocator.alloc(layout).is_null());
+            assert!(allocator.alloc_zeroed(layout).is_null());
+            let mut old = [0u8; 16];
+            assert!(allocator.realloc(old.as_mut_ptr(), layout, 64).is_null());
+        });
+        assert_eq!(events, []);
+    }
+}
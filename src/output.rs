@@ -0,0 +1,138 @@
+//! What the backends that write a file instead of sending the events to Tracy share: the data
+//! each thread records, and writing it once, by `flush_and_wait`, the panic hook in `crate::panic`
+//! or at exit, whichever comes first.
+
+use std::{
+    io,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex, MutexGuard, Once, PoisonError,
+    },
+};
+
+/// Recordings with a poisoned lock are still written.
+pub(crate) fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// The data `T` of each thread that recorded any, and where and how it is written.
+pub(crate) struct Output<T> {
+    /// The threads keep adding to theirs, so they can be written while the threads are running.
+    threads: Mutex<Vec<Arc<Mutex<T>>>>,
+    stopped: AtomicBool,
+    at_exit_registered: Once,
+    /// For the error message, like "the Chrome trace".
+    what: &'static str,
+    path: fn() -> PathBuf,
+    write: fn(&Path) -> io::Result<()>,
+    /// Calls `write_and_stop` on this; registered with the first thread.
+    at_exit: extern "C" fn(),
+}
+
+impl<T> Output<T> {
+    pub(crate) const fn new(
+        what: &'static str,
+        path: fn() -> PathBuf,
+        write: fn(&Path) -> io::Result<()>,
+        at_exit: extern "C" fn(),
+    ) -> Self {
+        Self {
+            threads: Mutex::new(Vec::new()),
+            stopped: AtomicBool::new(false),
+            at_exit_registered: Once::new(),
+            what,
+            path,
+            write,
+            at_exit,
+        }
+    }
+
+    /// Adds the data of a new thread.
+    pub(crate) fn register(&self, data: T) -> Arc<Mutex<T>> {
+        self.at_exit_registered.call_once(|| unsafe {
+            libc::atexit(self.at_exit);
+        });
+
+        let data = Arc::new(Mutex::new(data));
+        lock(&self.threads).push(Arc::clone(&data));
+        data
+    }
+
+    /// The data of all threads registered so far.
+    pub(crate) fn threads(&self) -> Vec<Arc<Mutex<T>>> {
+        lock(&self.threads).clone()
+    }
+
+    /// Whether `write_and_stop` was called; nothing should be recorded anymore.
+    pub(crate) fn is_stopped(&self) -> bool {
+        self.stopped.load(Ordering::Relaxed)
+    }
+
+    /// Writes to the output path and stops recording, unless that happened before.
+    ///
+    /// Returns whether the output was written.
+    pub(crate) fn write_and_stop(&self) -> bool {
+        if self.stopped.swap(true, Ordering::Relaxed) {
+            return false;
+        }
+        let path = (self.path)();
+        match (self.write)(&path) {
+            Ok(()) => true,
+            Err(err) => {
+                eprintln!("tracy-rs: failed to write {} to {}: {}", self.what, path.display(), err);
+                false
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{env, fmt::Write as _, fs, process, sync::mpsc, thread};
+
+    static OUTPUT: Output<String> = Output::new("the test output", path, write, at_exit);
+
+    fn path() -> PathBuf {
+        env::temp_dir().join(format!("tracy-rs-output-{}", process::id()))
+    }
+
+    fn write(path: &Path) -> io::Result<()> {
+        let mut output = String::new();
+        for thread in OUTPUT.threads() {
+            writeln!(output, "{}", lock(&thread)).unwrap();
+        }
+        fs::write(path, output)
+    }
+
+    extern "C" fn at_exit() {
+        OUTPUT.write_and_stop();
+    }
+
+    #[test]
+    fn writes_running_threads_once() {
+        let (recorded_tx, recorded_rx) = mpsc::channel();
+        let (exit_tx, exit_rx) = mpsc::channel::<()>();
+        let running = thread::spawn(move || {
+            let data = OUTPUT.register(String::new());
+            lock(&data).push_str("still running");
+            recorded_tx.send(()).unwrap();
+            let _ = exit_rx.recv();
+        });
+        recorded_rx.recv().unwrap();
+
+        assert!(!OUTPUT.is_stopped());
+        assert!(OUTPUT.write_and_stop());
+        assert!(OUTPUT.is_stopped());
+        let written = fs::read_to_string(path()).unwrap();
+        fs::remove_file(path()).unwrap();
+        //nor at exit
+        assert!(!OUTPUT.write_and_stop());
+        assert!(!path().exists());
+        drop(exit_tx);
+        running.join().unwrap();
+
+        assert_eq!(written, "still running\n");
+    }
+}
@@ -0,0 +1,108 @@
+//! A panic hook that records panics in the trace.
+
+use crate::{
+    backend::{Active, Backend},
+    colors, message_color,
+};
+use std::{
+    backtrace::Backtrace,
+    cell::RefCell,
+    fmt::Write,
+    panic,
+    sync::atomic::{AtomicBool, Ordering},
+    time::Duration,
+};
+
+static INSTALLED: AtomicBool = AtomicBool::new(false);
+
+/// Installs a panic hook that sends the panic message, its location and optionally a backtrace as
+/// a red message, ends the open zones of the panicking thread, calls `flush_and_wait` with
+/// `flush_timeout` and then calls the previously installed hook.
+///
+/// Flushing stops the profiler, so nothing is recorded after a panic, even if it is caught. Pass
+/// `Duration::ZERO` to skip it if the program goes on after panics.
+///
+/// Once the hook is installed, each zone is also pushed to a thread-local list when it begins and
+/// removed when it ends, so the hook knows which zones are open; this makes zones slightly more
+/// expensive. Zones that began before the hook was installed are not ended by it.
+pub fn install_hook(backtrace: bool, flush_timeout: Duration) {
+    let previous = panic::take_hook();
+    panic::set_hook(Box::new(move |info| {
+        let payload = info.payload().downcast_ref::<&str>().copied()
+            .or_else(|| info.payload().downcast_ref::<String>().map(String::as_str))
+            .unwrap_or("Box<dyn Any>");
+        let mut text = format!("panic: {}", payload);
+        if let Some(location) = info.location() {
+            write!(text, "\nat {}:{}:{}", location.file(), location.line(), location.column()).unwrap();
+        }
+        if backtrace {
+            write!(text, "\n{}", Backtrace::force_capture()).unwrap();
+        }
+        message_color(&text, colors::Red);
+
+        end_open_zones();
+        if flush_timeout > Duration::ZERO {
+            crate::flush_and_wait(flush_timeout);
+        }
+
+        previous(info);
+    }));
+    INSTALLED.store(true, Ordering::Relaxed);
+}
+
+struct OpenZones {
+    next_id: u32,
+    zones: Vec<(u32, <Active as Backend>::Zone)>,
+}
+
+thread_local! {
+    static OPEN_ZONES: RefCell<OpenZones> = const { RefCell::new(OpenZones { next_id: 1, zones: Vec::new() }) };
+}
+
+/// Tracks `zone` if the hook is installed.
+///
+/// Returns the id to pass to `zone_ended`, or 0 if the zone isn't tracked.
+#[inline]
+pub(crate) fn zone_begun(zone: <Active as Backend>::Zone) -> u32 {
+    if !INSTALLED.load(Ordering::Relaxed) {
+        return 0;
+    }
+    OPEN_ZONES.try_with(|open| {
+        let mut open = open.borrow_mut();
+        let id = open.next_id;
+        open.next_id = id.checked_add(1).unwrap_or(1);
+        open.zones.push((id, zone));
+        id
+    }).unwrap_or(0)
+}
+
+/// Stops tracking the zone with `id`.
+///
+/// Returns whether the zone still needs to be ended, which is not the case if the hook ended it.
+#[inline]
+pub(crate) fn zone_ended(id: u32) -> bool {
+    if id == 0 {
+        return true;
+    }
+    //zones can be dropped in any order
+    OPEN_ZONES.try_with(|open| {
+        let mut open = open.borrow_mut();
+        match open.zones.iter().rposition(|&(open_id, _)| open_id == id) {
+            Some(i) => {
+                open.zones.remove(i);
+                true
+            },
+            None => false,
+        }
+    }).unwrap_or(true)
+}
+
+fn end_open_zones() {
+    let _ = OPEN_ZONES.try_with(|open| {
+        if let Ok(mut open) = open.try_borrow_mut() {
+            for (_, zone) in open.zones.drain(..).rev() {
+                Active::zone_end(zone);
+            }
+        }
+    });
+}
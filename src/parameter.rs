@@ -0,0 +1,275 @@
+//! Values that can be changed from the viewer while the program is running.
+//!
+//! ```no_run
+//! use tracy_rs::{cstr, parameter::{BoolParameter, IntParameter}};
+//!
+//! static VSYNC: BoolParameter = BoolParameter::new(cstr!("vsync"), true);
+//! static LOD_BIAS: IntParameter = IntParameter::new(cstr!("LOD bias"), 0, -4, 4);
+//!
+//! VSYNC.register();
+//! LOD_BIAS.register();
+//! tracy_rs::parameter::on_change(|parameter| println!("{:?} changed", parameter.name()));
+//! assert!(VSYNC.get());
+//! ```
+
+use crate::backend::{Active, Backend};
+use std::{
+    ffi::CStr,
+    sync::{
+        atomic::{AtomicBool, AtomicI32, AtomicU32, Ordering},
+        Arc, Mutex, Once,
+    },
+};
+
+/// A parameter that can be registered with the viewer.
+pub trait Parameter: Sync {
+    fn name(&self) -> &'static CStr;
+
+    fn is_bool(&self) -> bool;
+
+    /// The current value, as sent to the viewer.
+    fn raw_value(&self) -> i32;
+
+    /// Applies a value from the viewer.
+    ///
+    /// Returns the value that was actually applied, e.g. after clamping.
+    fn apply_raw_value(&self, value: i32) -> i32;
+
+    #[doc(hidden)]
+    fn index(&self) -> &AtomicU32;
+}
+
+const UNREGISTERED: u32 = u32::MAX;
+
+pub struct BoolParameter {
+    name: &'static CStr,
+    value: AtomicBool,
+    index: AtomicU32,
+}
+
+impl BoolParameter {
+    pub const fn new(name: &'static CStr, value: bool) -> Self {
+        Self {
+            name,
+            value: AtomicBool::new(value),
+            index: AtomicU32::new(UNREGISTERED),
+        }
+    }
+
+    #[inline]
+    pub fn get(&self) -> bool {
+        self.value.load(Ordering::Relaxed)
+    }
+
+    /// Sets the value and shows it in the viewer, if registered.
+    pub fn set(&'static self, value: bool) {
+        self.value.store(value, Ordering::Relaxed);
+        update_viewer(self);
+    }
+
+    #[inline]
+    pub fn register(&'static self) {
+        register(self);
+    }
+}
+
+impl Parameter for BoolParameter {
+    fn name(&self) -> &'static CStr {
+        self.name
+    }
+
+    fn is_bool(&self) -> bool {
+        true
+    }
+
+    fn raw_value(&self) -> i32 {
+        self.get() as i32
+    }
+
+    fn apply_raw_value(&self, value: i32) -> i32 {
+        self.value.store(value != 0, Ordering::Relaxed);
+        self.raw_value()
+    }
+
+    fn index(&self) -> &AtomicU32 {
+        &self.index
+    }
+}
+
+/// An integer parameter, kept between `min` and `max` inclusively.
+pub struct IntParameter {
+    name: &'static CStr,
+    value: AtomicI32,
+    index: AtomicU32,
+    pub min: i32,
+    pub max: i32,
+}
+
+impl IntParameter {
+    /// Panics if `value` is not between `min` and `max`.
+    pub const fn new(name: &'static CStr, value: i32, min: i32, max: i32) -> Self {
+        assert!(min <= value && value <= max, "parameter value must be between min and max");
+        Self {
+            name,
+            value: AtomicI32::new(value),
+            index: AtomicU32::new(UNREGISTERED),
+            min,
+            max,
+        }
+    }
+
+    #[inline]
+    pub fn get(&self) -> i32 {
+        self.value.load(Ordering::Relaxed)
+    }
+
+    /// Sets the value, clamped to `min` and `max`, and shows it in the viewer, if registered.
+    pub fn set(&'static self, value: i32) {
+        self.apply_raw_value(value);
+        update_viewer(self);
+    }
+
+    #[inline]
+    pub fn register(&'static self) {
+        register(self);
+    }
+}
+
+impl Parameter for IntParameter {
+    fn name(&self) -> &'static CStr {
+        self.name
+    }
+
+    fn is_bool(&self) -> bool {
+        false
+    }
+
+    fn raw_value(&self) -> i32 {
+        self.get()
+    }
+
+    fn apply_raw_value(&self, value: i32) -> i32 {
+        let value = value.max(self.min).min(self.max);
+        self.value.store(value, Ordering::Relaxed);
+        value
+    }
+
+    fn index(&self) -> &AtomicU32 {
+        &self.index
+    }
+}
+
+type ChangeCallback = Arc<dyn Fn(&'static dyn Parameter) + Send + Sync>;
+
+static PARAMETERS: Mutex<Vec<&'static dyn Parameter>> = Mutex::new(Vec::new());
+static CALLBACKS: Mutex<Vec<ChangeCallback>> = Mutex::new(Vec::new());
+static REGISTER_CALLBACK: Once = Once::new();
+
+/// Shows `parameter` in the viewer, so its value can be changed there.
+///
+/// Registering a parameter again does nothing.
+pub fn register(parameter: &'static dyn Parameter) {
+    REGISTER_CALLBACK.call_once(|| Active::parameter_register(parameter_changed));
+
+    let mut parameters = PARAMETERS.lock().unwrap();
+    if parameter.index().load(Ordering::Relaxed) != UNREGISTERED {
+        return;
+    }
+    let index = parameters.len() as u32;
+    parameters.push(parameter);
+    parameter.index().store(index, Ordering::Relaxed);
+    update_viewer(parameter);
+}
+
+/// Calls `callback` after a parameter was changed from the viewer.
+///
+/// The callback runs on Tracy's thread.
+pub fn on_change<F>(callback: F)
+where
+    F: Fn(&'static dyn Parameter) + Send + Sync + 'static,
+{
+    CALLBACKS.lock().unwrap().push(Arc::new(callback));
+}
+
+fn update_viewer(parameter: &dyn Parameter) {
+    let index = parameter.index().load(Ordering::Relaxed);
+    if index != UNREGISTERED {
+        Active::parameter_setup(index, parameter.name(), parameter.is_bool(), parameter.raw_value());
+    }
+}
+
+fn parameter_changed(index: u32, value: i32) {
+    let parameter = match PARAMETERS.lock() {
+        Ok(parameters) => match parameters.get(index as usize) {
+            Some(&parameter) => parameter,
+            None => return,
+        },
+        Err(_) => return,
+    };
+
+    if parameter.apply_raw_value(value) != value {
+        update_viewer(parameter);
+    }
+
+    //don't hold the lock while calling the callbacks, which may register more
+    let callbacks = match CALLBACKS.lock() {
+        Ok(callbacks) => callbacks.clone(),
+        Err(_) => return,
+    };
+    for callback in callbacks.iter() {
+        callback(parameter);
+    }
+}
+
+#[cfg(all(test, feature = "test_backend"))]
+mod tests {
+    use super::*;
+    use crate::cstr;
+
+    #[test]
+    fn int_clamping() {
+        static PARAMETER: IntParameter = IntParameter::new(cstr!("clamped"), 0, -4, 4);
+        PARAMETER.set(7);
+        assert_eq!(PARAMETER.get(), 4);
+        PARAMETER.set(-7);
+        assert_eq!(PARAMETER.get(), -4);
+        assert_eq!(PARAMETER.apply_raw_value(2), 2);
+        assert_eq!(PARAMETER.apply_raw_value(i32::MAX), 4);
+    }
+
+    #[test]
+    fn register_twice() {
+        static PARAMETER: BoolParameter = BoolParameter::new(cstr!("registered twice"), false);
+        PARAMETER.register();
+        let index = PARAMETER.index().load(Ordering::Relaxed);
+        assert_ne!(index, UNREGISTERED);
+        PARAMETER.register();
+        register(&PARAMETER);
+        assert_eq!(PARAMETER.index().load(Ordering::Relaxed), index);
+        let parameters = PARAMETERS.lock().unwrap();
+        assert_eq!(parameters.iter().filter(|parameter| parameter.name() == PARAMETER.name()).count(), 1);
+    }
+
+    #[test]
+    fn changed_from_viewer() {
+        static BOOL: BoolParameter = BoolParameter::new(cstr!("changed bool"), false);
+        static INT: IntParameter = IntParameter::new(cstr!("changed int"), 0, 0, 10);
+        static CHANGED: Mutex<Vec<(&CStr, i32)>> = Mutex::new(Vec::new());
+        BOOL.register();
+        INT.register();
+        on_change(|parameter| {
+            //other tests' parameters may change concurrently
+            if parameter.name() == BOOL.name() || parameter.name() == INT.name() {
+                CHANGED.lock().unwrap().push((parameter.name(), parameter.raw_value()));
+            }
+        });
+
+        parameter_changed(BOOL.index().load(Ordering::Relaxed), 1);
+        parameter_changed(INT.index().load(Ordering::Relaxed), 20);
+        //unknown indices are ignored
+        parameter_changed(UNREGISTERED - 1, 1);
+        assert!(BOOL.get());
+        assert_eq!(INT.get(), 10);
+        assert_eq!(*CHANGED.lock().unwrap(), [(BOOL.name(), 1), (INT.name(), 10)]);
+    }
+}
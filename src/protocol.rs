@@ -0,0 +1,666 @@
+//! The network protocol between the Tracy client and the viewer, as far as needed to record and
+//! read traces without the viewer.
+//!
+//! The layouts follow TracyProtocol.hpp and TracyQueue.hpp of Tracy 0.11; other versions are
+//! rejected during the handshake by comparing `PROTOCOL_VERSION`.
+//!
+//! After the handshake the client sends a stream of LZ4 compressed frames, each prefixed by its
+//! compressed size as `u32`. A frame decompresses to a sequence of whole events, each starting
+//! with its `QueueType`. The viewer answers events that refer to data by address, e.g. source
+//! locations and strings, with a `ServerQuery`, to which the client replies with another event.
+
+use std::{convert::TryInto, error, fmt, io};
+
+/// Starts the recordings written by tracy-record.
+///
+/// These are not the .tracy files of the viewer, but the data as received from the client:
+/// `RECORDING_MAGIC`, `RECORDING_VERSION` and `PROTOCOL_VERSION` as `u32`, the welcome message, a
+/// `u8` that is 1 if the on-demand payload follows, and records until the end of the file, each
+/// starting with a `u8` tag.
+pub const RECORDING_MAGIC: &[u8; 8] = b"TracyRsR";
+pub const RECORDING_VERSION: u32 = 2;
+
+/// A compressed frame, prefixed by its size as `u32`.
+pub const RECORD_FRAME: u8 = 0;
+/// A query sent to the client, as `u8`, and its `u64` argument. The client replies to the queries
+/// in the order they were sent, in later frames.
+pub const RECORD_QUERY: u8 = 1;
+
+/// Sent by the viewer to start the handshake, followed by its `PROTOCOL_VERSION` as `u32`.
+pub const HANDSHAKE_SHIBBOLETH: &[u8; 8] = b"TracyPrf";
+
+/// The client answers the handshake with one of these as `u8`.
+pub const HANDSHAKE_PENDING: u8 = 0;
+pub const HANDSHAKE_WELCOME: u8 = 1;
+pub const HANDSHAKE_PROTOCOL_MISMATCH: u8 = 2;
+pub const HANDSHAKE_NOT_AVAILABLE: u8 = 3;
+pub const HANDSHAKE_DROPPED: u8 = 4;
+
+/// The most the client puts into one frame before compressing it.
+pub const TARGET_FRAME_SIZE: usize = 256 * 1024;
+
+/// The size of a `ServerQuery` packet; the query as `u8`, followed by a `u64` and a `u32`.
+pub const SERVER_QUERY_PACKET_SIZE: usize = 13;
+
+/// Queries the viewer sends to the client.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[repr(u8)]
+pub enum ServerQuery {
+    Terminate,
+    String,
+    ThreadString,
+    SourceLocation,
+    PlotName,
+    FrameName,
+    Parameter,
+    FiberName,
+    ExternalName,
+    Disconnect,
+    CallstackFrame,
+    Symbol,
+    SymbolCode,
+    SourceCode,
+    DataTransfer,
+    DataTransferPart,
+}
+
+impl ServerQuery {
+    const ALL: &'static [ServerQuery] = &[
+        ServerQuery::Terminate,
+        ServerQuery::String,
+        ServerQuery::ThreadString,
+        ServerQuery::SourceLocation,
+        ServerQuery::PlotName,
+        ServerQuery::FrameName,
+        ServerQuery::Parameter,
+        ServerQuery::FiberName,
+        ServerQuery::ExternalName,
+        ServerQuery::Disconnect,
+        ServerQuery::CallstackFrame,
+        ServerQuery::Symbol,
+        ServerQuery::SymbolCode,
+        ServerQuery::SourceCode,
+        ServerQuery::DataTransfer,
+        ServerQuery::DataTransferPart,
+    ];
+
+    #[inline]
+    pub fn from_u8(query: u8) -> Option<Self> {
+        Self::ALL.get(query as usize).copied()
+    }
+
+    pub fn packet(self, ptr: u64, extra: u32) -> [u8; SERVER_QUERY_PACKET_SIZE] {
+        let mut packet = [0; SERVER_QUERY_PACKET_SIZE];
+        packet[0] = self as u8;
+        packet[1..9].copy_from_slice(&ptr.to_le_bytes());
+        packet[9..].copy_from_slice(&extra.to_le_bytes());
+        packet
+    }
+}
+
+/// `Welcome::flags`
+pub const WELCOME_FLAG_ON_DEMAND: u8 = 1 << 0;
+pub const WELCOME_FLAG_IS_APPLE: u8 = 1 << 1;
+pub const WELCOME_FLAG_CODE_TRANSFER: u8 = 1 << 2;
+pub const WELCOME_FLAG_COMBINE_SAMPLES: u8 = 1 << 3;
+pub const WELCOME_FLAG_IDENTIFY_SAMPLES: u8 = 1 << 4;
+
+const PROGRAM_NAME_SIZE: usize = 64;
+const HOST_INFO_SIZE: usize = 1024;
+
+/// The size of the welcome message the client sends after accepting the handshake.
+pub const WELCOME_SIZE: usize = 8 * 8 + 2 + 12 + 4 + PROGRAM_NAME_SIZE + HOST_INFO_SIZE;
+
+/// The size of the message that follows the welcome message if the client was built with
+/// "on_demand"; the number of frames and the time before the viewer connected.
+pub const ON_DEMAND_PAYLOAD_SIZE: usize = 16;
+
+/// Information about the client, sent after the handshake.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Welcome {
+    /// Converts the timestamps of the events to nanoseconds.
+    pub timer_mul: f64,
+    pub init_begin: i64,
+    pub init_end: i64,
+    pub resolution: u64,
+    /// When the program was started, in seconds since the Unix epoch.
+    pub epoch: u64,
+    pub exec_time: u64,
+    pub pid: u64,
+    pub sampling_period: i64,
+    pub flags: u8,
+    pub cpu_arch: u8,
+    pub cpu_manufacturer: String,
+    pub cpu_id: u32,
+    pub program_name: String,
+    pub host_info: String,
+}
+
+impl Welcome {
+    pub fn parse(data: &[u8; WELCOME_SIZE]) -> Self {
+        let mut reader = Reader(data);
+        Self {
+            timer_mul: reader.f64(),
+            init_begin: reader.i64(),
+            init_end: reader.i64(),
+            resolution: reader.u64(),
+            epoch: reader.u64(),
+            exec_time: reader.u64(),
+            pid: reader.u64(),
+            sampling_period: reader.i64(),
+            flags: reader.u8(),
+            cpu_arch: reader.u8(),
+            cpu_manufacturer: c_string(reader.bytes(12)),
+            cpu_id: reader.u32(),
+            program_name: c_string(reader.bytes(PROGRAM_NAME_SIZE)),
+            host_info: c_string(reader.bytes(HOST_INFO_SIZE)),
+        }
+    }
+
+    /// Converts a timestamp of an event to nanoseconds since the client was initialized.
+    #[inline]
+    pub fn to_ns(&self, time: i64) -> i64 {
+        ((time - self.init_begin) as f64 * self.timer_mul) as i64
+    }
+}
+
+fn c_string(bytes: &[u8]) -> String {
+    let len = bytes.iter().position(|&b| b == 0).unwrap_or(bytes.len());
+    String::from_utf8_lossy(&bytes[..len]).into_owned()
+}
+
+#[derive(Debug)]
+pub enum Error {
+    Io(io::Error),
+    /// The client answered the handshake with this status.
+    Handshake(u8),
+    /// A frame is not valid LZ4 data.
+    Decompress,
+    /// An event has an unknown type; the rest of its frame can't be read.
+    UnknownEvent(u8),
+    /// An event exceeds its frame.
+    Truncated(QueueType),
+    /// Not a recording, or one written for a different protocol version.
+    Format(String),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::Io(err) => err.fmt(f),
+            Error::Handshake(HANDSHAKE_PROTOCOL_MISMATCH) => write!(f, "the client uses a different protocol version"),
+            Error::Handshake(HANDSHAKE_NOT_AVAILABLE) => write!(f, "the client is not ready for a connection"),
+            Error::Handshake(HANDSHAKE_DROPPED) => write!(f, "the client dropped the connection, it was already connected to another viewer"),
+            Error::Handshake(status) => write!(f, "unexpected handshake status {}", status),
+            Error::Decompress => write!(f, "invalid LZ4 data"),
+            Error::UnknownEvent(ty) => write!(f, "unknown event type {}", ty),
+            Error::Truncated(ty) => write!(f, "truncated {:?} event", ty),
+            Error::Format(msg) => f.write_str(msg),
+        }
+    }
+}
+
+impl error::Error for Error {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            Error::Io(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(err: io::Error) -> Self {
+        Error::Io(err)
+    }
+}
+
+/// Decompresses the frames of the stream.
+///
+/// The client compresses the frames as one LZ4 stream, so a frame may refer to the last 64 KiB of
+/// the frames before it.
+pub struct Decompressor {
+    window: Vec<u8>,
+}
+
+const LZ4_WINDOW_SIZE: usize = 64 * 1024;
+
+impl Decompressor {
+    pub fn new() -> Self {
+        Self { window: Vec::with_capacity(LZ4_WINDOW_SIZE + TARGET_FRAME_SIZE) }
+    }
+
+    /// Decompresses the next frame.
+    pub fn decompress(&mut self, frame: &[u8]) -> Result<&[u8], Error> {
+        if self.window.len() > LZ4_WINDOW_SIZE {
+            self.window.drain(..self.window.len() - LZ4_WINDOW_SIZE);
+        }
+        let start = self.window.len();
+        if decompress_block(frame, &mut self.window, start + TARGET_FRAME_SIZE).is_none() {
+            //the stream can't continue after a broken frame
+            self.window.clear();
+            return Err(Error::Decompress);
+        }
+        Ok(&self.window[start..])
+    }
+}
+
+impl Default for Decompressor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Decompresses an LZ4 block, appending to `out`, whose previous contents serve as the
+/// dictionary. Returns `None` for invalid data or if `out` would grow beyond `limit`.
+fn decompress_block(mut input: &[u8], out: &mut Vec<u8>, limit: usize) -> Option<()> {
+    fn length(input: &mut &[u8], mut len: usize) -> Option<usize> {
+        if len == 15 {
+            loop {
+                let (&b, rest) = input.split_first()?;
+                *input = rest;
+                len += b as usize;
+                if b != 255 {
+                    break;
+                }
+            }
+        }
+        Some(len)
+    }
+
+    loop {
+        let (&token, rest) = input.split_first()?;
+        input = rest;
+
+        let literals = length(&mut input, (token >> 4) as usize)?;
+        if literals > input.len() || out.len() + literals > limit {
+            return None;
+        }
+        out.extend_from_slice(&input[..literals]);
+        input = &input[literals..];
+        if input.is_empty() {
+            return Some(());
+        }
+
+        if input.len() < 2 {
+            return None;
+        }
+        let offset = u16::from_le_bytes([input[0], input[1]]) as usize;
+        input = &input[2..];
+        let len = length(&mut input, (token & 0xf) as usize)? + 4;
+        if offset == 0 || offset > out.len() || out.len() + len > limit {
+            return None;
+        }
+        //the match may overlap the bytes it produces
+        let start = out.len() - offset;
+        for i in 0..len {
+            let b = out[start + i];
+            out.push(b);
+        }
+    }
+}
+
+macro_rules! queue_types {
+    ($($(#[$attr: meta])* $name: ident = $size: expr,)*) => {
+        /// The type of an event, the first byte of every event.
+        #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+        #[repr(u8)]
+        pub enum QueueType {
+            $($(#[$attr])* $name,)*
+        }
+
+        impl QueueType {
+            const ALL: &'static [QueueType] = &[$(QueueType::$name,)*];
+            const SIZES: &'static [usize] = &[$($size,)*];
+        }
+    };
+}
+
+// the sizes exclude the type and any trailing string
+queue_types! {
+    ZoneText = 0,
+    ZoneName = 0,
+    Message = 8,
+    MessageColor = 11,
+    MessageCallstack = 8,
+    MessageColorCallstack = 11,
+    MessageAppInfo = 8,
+    ZoneBeginAllocSrcLoc = 8,
+    ZoneBeginAllocSrcLocCallstack = 8,
+    CallstackSerial = 0,
+    Callstack = 0,
+    CallstackAlloc = 0,
+    CallstackSample = 12,
+    CallstackSampleContextSwitch = 12,
+    FrameImage = 9,
+    ZoneBegin = 16,
+    ZoneBeginCallstack = 16,
+    ZoneEnd = 8,
+    LockWait = 16,
+    LockObtain = 16,
+    LockRelease = 12,
+    LockSharedWait = 16,
+    LockSharedObtain = 16,
+    LockSharedRelease = 16,
+    LockName = 4,
+    MemAlloc = 26,
+    MemAllocNamed = 26,
+    MemFree = 20,
+    MemFreeNamed = 20,
+    MemAllocCallstack = 26,
+    MemAllocCallstackNamed = 26,
+    MemFreeCallstack = 20,
+    MemFreeCallstackNamed = 20,
+    MemDiscard = 20,
+    MemDiscardCallstack = 20,
+    GpuZoneBegin = 23,
+    GpuZoneBeginCallstack = 23,
+    GpuZoneBeginAllocSrcLoc = 15,
+    GpuZoneBeginAllocSrcLocCallstack = 15,
+    GpuZoneEnd = 15,
+    GpuZoneBeginSerial = 23,
+    GpuZoneBeginCallstackSerial = 23,
+    GpuZoneBeginAllocSrcLocSerial = 15,
+    GpuZoneBeginAllocSrcLocCallstackSerial = 15,
+    GpuZoneEndSerial = 15,
+    PlotDataInt = 24,
+    PlotDataFloat = 20,
+    PlotDataDouble = 24,
+    ContextSwitch = 22,
+    ThreadWakeup = 15,
+    GpuTime = 11,
+    GpuContextName = 1,
+    CallstackFrameSize = 9,
+    SymbolInformation = 12,
+    ExternalNameMetadata = 24,
+    SymbolCodeMetadata = 20,
+    SourceCodeMetadata = 16,
+    FiberEnter = 24,
+    FiberLeave = 12,
+    Terminate = 0,
+    KeepAlive = 0,
+    ThreadContext = 4,
+    GpuCalibration = 25,
+    GpuTimeSync = 17,
+    Crash = 0,
+    CrashReport = 16,
+    ZoneValidation = 4,
+    ZoneColor = 3,
+    ZoneValue = 8,
+    FrameMarkMsg = 16,
+    FrameMarkMsgStart = 16,
+    FrameMarkMsgEnd = 16,
+    FrameVsync = 12,
+    SourceLocation = 31,
+    LockAnnounce = 21,
+    LockTerminate = 12,
+    LockMark = 16,
+    MessageLiteral = 16,
+    MessageLiteralColor = 19,
+    MessageLiteralCallstack = 16,
+    MessageLiteralColorCallstack = 19,
+    GpuNewContext = 27,
+    CallstackFrame = 16,
+    SysTimeReport = 12,
+    SysPowerReport = 24,
+    TidToPid = 16,
+    HwSampleCpuCycle = 16,
+    HwSampleInstructionRetired = 16,
+    HwSampleCacheReference = 16,
+    HwSampleCacheMiss = 16,
+    HwSampleBranchRetired = 16,
+    HwSampleBranchMiss = 16,
+    PlotConfig = 15,
+    ParamSetup = 17,
+    AckServerQueryNoop = 0,
+    AckSourceCodeNotAvailable = 4,
+    AckSymbolCodeNotAvailable = 0,
+    CpuTopology = 16,
+    /// Followed by a `u16` length and the string, which belongs to the next event.
+    SingleStringData = 0,
+    /// Like `SingleStringData`, for events with two strings.
+    SecondStringData = 0,
+    MemNamePayload = 8,
+    ThreadGroupHint = 8,
+    /// The events from here on carry a `u64` address, followed by a `u16` length and the data;
+    /// `FrameImageData`, `SymbolCode` and `SourceCode` use a `u32` length.
+    StringData = 8,
+    ThreadName = 8,
+    PlotName = 8,
+    SourceLocationPayload = 8,
+    CallstackPayload = 8,
+    CallstackAllocPayload = 8,
+    FrameName = 8,
+    FrameImageData = 8,
+    ExternalName = 8,
+    ExternalThreadName = 8,
+    SymbolCode = 8,
+    SourceCode = 8,
+    FiberName = 8,
+}
+
+impl QueueType {
+    #[inline]
+    pub fn from_u8(ty: u8) -> Option<Self> {
+        Self::ALL.get(ty as usize).copied()
+    }
+
+    /// The size of the event after the type, excluding any trailing string.
+    #[inline]
+    pub fn size(self) -> usize {
+        Self::SIZES[self as usize]
+    }
+
+    /// The size of the length of the trailing string, if there is one.
+    #[inline]
+    fn string_len_size(self) -> usize {
+        match self {
+            QueueType::FrameImageData | QueueType::SymbolCode | QueueType::SourceCode => 4,
+            QueueType::SingleStringData | QueueType::SecondStringData => 2,
+            ty if ty as u8 >= QueueType::StringData as u8 => 2,
+            _ => 0,
+        }
+    }
+}
+
+/// An event of a decompressed frame.
+#[derive(Clone, Copy, Debug)]
+pub struct Event<'a> {
+    pub ty: QueueType,
+    /// The fields after the type.
+    pub data: &'a [u8],
+    /// The trailing string or payload.
+    pub string: Option<&'a [u8]>,
+}
+
+impl<'a> Event<'a> {
+    /// A reader for `data`.
+    #[inline]
+    pub fn reader(&self) -> Reader<'a> {
+        Reader(self.data)
+    }
+
+    /// The trailing string, lossily converted to UTF-8.
+    pub fn text(&self) -> String {
+        String::from_utf8_lossy(self.string.unwrap_or_default()).into_owned()
+    }
+}
+
+/// Reads little-endian fields.
+///
+/// Panics when reading past the end; `Event::data` always has the size of its type.
+#[derive(Clone, Copy, Debug)]
+pub struct Reader<'a>(pub &'a [u8]);
+
+macro_rules! reader_fns {
+    ($($name: ident: $ty: ty,)*) => {
+        $(
+            #[inline]
+            pub fn $name(&mut self) -> $ty {
+                let (bytes, rest) = self.0.split_at(std::mem::size_of::<$ty>());
+                self.0 = rest;
+                <$ty>::from_le_bytes(bytes.try_into().unwrap())
+            }
+        )*
+    };
+}
+
+impl<'a> Reader<'a> {
+    reader_fns! {
+        u8: u8,
+        i8: i8,
+        u16: u16,
+        u32: u32,
+        i32: i32,
+        u64: u64,
+        i64: i64,
+        f32: f32,
+        f64: f64,
+    }
+
+    #[inline]
+    pub fn bytes(&mut self, len: usize) -> &'a [u8] {
+        let (bytes, rest) = self.0.split_at(len);
+        self.0 = rest;
+        bytes
+    }
+
+    #[inline]
+    pub fn skip(&mut self, len: usize) -> &mut Self {
+        self.0 = &self.0[len..];
+        self
+    }
+}
+
+/// The events of a decompressed frame.
+///
+/// Stops after the first error, since the rest of the frame can't be read; the next frame starts
+/// with a new event again.
+pub struct Events<'a>(&'a [u8]);
+
+impl<'a> Events<'a> {
+    #[inline]
+    pub fn new(frame: &'a [u8]) -> Self {
+        Events(frame)
+    }
+
+    fn parse(&mut self) -> Result<Event<'a>, Error> {
+        let ty = QueueType::from_u8(self.0[0]).ok_or(Error::UnknownEvent(self.0[0]))?;
+        let rest = &self.0[1..];
+        let size = ty.size();
+        let len_size = ty.string_len_size();
+        if rest.len() < size + len_size {
+            return Err(Error::Truncated(ty));
+        }
+        let (data, rest) = rest.split_at(size);
+        let (string, rest) = match len_size {
+            0 => (None, rest),
+            _ => {
+                let (len, rest) = rest.split_at(len_size);
+                let len = match *len {
+                    [a, b] => u16::from_le_bytes([a, b]) as usize,
+                    [a, b, c, d] => u32::from_le_bytes([a, b, c, d]) as usize,
+                    _ => unreachable!(),
+                };
+                if rest.len() < len {
+                    return Err(Error::Truncated(ty));
+                }
+                let (string, rest) = rest.split_at(len);
+                (Some(string), rest)
+            },
+        };
+        self.0 = rest;
+        Ok(Event { ty, data, string })
+    }
+}
+
+impl<'a> Iterator for Events<'a> {
+    type Item = Result<Event<'a>, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.0.is_empty() {
+            return None;
+        }
+        let event = self.parse();
+        if event.is_err() {
+            self.0 = &[];
+        }
+        Some(event)
+    }
+}
+
+/// The queries the viewer sends in response to `event` to learn about the data it refers to.
+///
+/// Returns the query and its argument; `known` filters out addresses that were already queried.
+pub fn queries_for(event: &Event, mut known: impl FnMut(ServerQuery, u64) -> bool) -> Vec<(ServerQuery, u64)> {
+    let mut queries = Vec::new();
+    let mut query = |query, ptr| {
+        if ptr != 0 && !known(query, ptr) {
+            queries.push((query, ptr));
+        }
+    };
+    let mut reader = event.reader();
+    match event.ty {
+        QueueType::ThreadContext => query(ServerQuery::ThreadString, reader.u32() as u64),
+        QueueType::ZoneBegin | QueueType::ZoneBeginCallstack => query(ServerQuery::SourceLocation, reader.skip(8).u64()),
+        QueueType::SourceLocation => {
+            query(ServerQuery::String, reader.u64());
+            query(ServerQuery::String, reader.u64());
+            query(ServerQuery::String, reader.u64());
+        },
+        QueueType::FrameMarkMsg | QueueType::FrameMarkMsgStart | QueueType::FrameMarkMsgEnd => {
+            query(ServerQuery::FrameName, reader.skip(8).u64());
+        },
+        QueueType::PlotDataInt | QueueType::PlotDataFloat | QueueType::PlotDataDouble => {
+            query(ServerQuery::PlotName, reader.u64());
+        },
+        QueueType::MessageLiteral | QueueType::MessageLiteralCallstack => {
+            query(ServerQuery::String, reader.skip(8).u64());
+        },
+        QueueType::MessageLiteralColor | QueueType::MessageLiteralColorCallstack => {
+            query(ServerQuery::String, reader.skip(11).u64());
+        },
+        _ => (),
+    }
+    queries
+}
+
+/// Whether `event` is the client's reply to a query.
+pub fn answers_query(event: &Event) -> bool {
+    matches!(
+        event.ty,
+        QueueType::StringData | QueueType::ThreadName | QueueType::PlotName | QueueType::FrameName
+            | QueueType::SourceLocation | QueueType::AckServerQueryNoop
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lz4_stream() {
+        let mut decompressor = Decompressor::new();
+        //"abc", then a match of 9 bytes overlapping its own output, and the literal "!"
+        let frame = decompressor.decompress(b"\x35abc\x03\x00\x10!").unwrap();
+        assert_eq!(frame, b"abcabcabcabc!");
+        //a match of 4 bytes from the previous frame
+        let frame = decompressor.decompress(b"\x00\x0d\x00\x00").unwrap();
+        assert_eq!(frame, b"abca");
+        assert!(decompressor.decompress(b"\x00\x20\x00\x00").is_err());
+    }
+
+    /// A wrong size would misread the rest of every frame containing that event.
+    #[cfg(all(feature = "enable", not(any(feature = "test_backend", feature = "chrome_trace", feature = "folded_stacks"))))]
+    #[test]
+    fn sizes_match_client() {
+        let mut count = 0;
+        let client_sizes = unsafe {
+            let sizes = crate::sys::___tracy_rs_queue_data_size(&mut count);
+            std::slice::from_raw_parts(sizes, count)
+        };
+        //the client includes the type
+        let sizes: Vec<_> = QueueType::SIZES.iter().map(|size| size + 1).collect();
+        assert_eq!(client_sizes, &sizes[..]);
+    }
+}
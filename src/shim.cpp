@@ -0,0 +1,219 @@
+// C entry points for parts of the Tracy client that TracyC.h does not expose.
+
+#include "client/TracyProfiler.hpp"
+#include "common/TracyQueue.hpp"
+
+#if __has_include("tracy/TracyC.h")
+#  include "tracy/TracyC.h"
+#else
+#  include "TracyC.h"
+#endif
+
+#include <cstddef>
+#include <cstdint>
+#include <cstdlib>
+
+#ifdef TRACY_ENABLE
+#  ifdef _WIN32
+#    include <winsock2.h>
+#    include <ws2tcpip.h>
+#    include <iphlpapi.h>
+#    include <vector>
+#  else
+#    ifdef __linux__
+#      include <dirent.h>
+#    endif
+#    include <arpa/inet.h>
+#    include <netinet/in.h>
+#    include <sys/socket.h>
+#    include <unistd.h>
+#  endif
+#endif
+
+typedef char* (*___tracy_rs_source_callback)(void* data, const char* filename, size_t* size);
+
+#if defined TRACY_ENABLE && !defined TRACY_NO_CODE_TRANSFER
+static ___tracy_rs_source_callback s_sourceCallback;
+
+static char* SourceCallback(void* data, const char* filename, size_t& size)
+{
+    return s_sourceCallback(data, filename, &size);
+}
+#endif
+
+#ifdef TRACY_ENABLE
+// Tracy keeps the port it bound to itself, so look for its listening socket in the range of ports
+// it tries, which it picks the same way.
+static void ListenPortRange(uint16_t& first, uint16_t& count)
+{
+    const char* userPort = getenv("TRACY_PORT");
+    first = userPort ? uint16_t(atoi(userPort)) : 0;
+    count = 1;
+    if (first == 0)
+    {
+#ifdef TRACY_DATA_PORT
+        first = TRACY_DATA_PORT;
+#else
+        first = 8086;
+        count = 20;
+#endif
+    }
+}
+
+static bool InRange(uint16_t port, uint16_t first, uint16_t count)
+{
+    return port >= first && port - first < count;
+}
+
+#ifdef _WIN32
+template<typename Table>
+static uint16_t FindListenPort(ULONG family, uint16_t first, uint16_t count)
+{
+    ULONG size = 0;
+    std::vector<char> buffer;
+    DWORD result;
+    do
+    {
+        buffer.resize(size);
+        result = GetExtendedTcpTable(buffer.data(), &size, FALSE, family, TCP_TABLE_OWNER_PID_LISTENER, 0);
+    }
+    while (result == ERROR_INSUFFICIENT_BUFFER);
+    if (result != NO_ERROR) return 0;
+
+    const auto table = (const Table*)buffer.data();
+    const auto pid = GetCurrentProcessId();
+    for (DWORD i = 0; i < table->dwNumEntries; i++)
+    {
+        const auto port = ntohs(uint16_t(table->table[i].dwLocalPort));
+        if (table->table[i].dwOwningPid == pid && InRange(port, first, count)) return port;
+    }
+    return 0;
+}
+#else
+// the port of fd if it is a listening TCP socket in the range, otherwise 0
+static uint16_t ListenPort(int fd, uint16_t first, uint16_t count)
+{
+    int value;
+    socklen_t len = sizeof(value);
+    if (getsockopt(fd, SOL_SOCKET, SO_ACCEPTCONN, &value, &len) != 0 || !value) return 0;
+    len = sizeof(value);
+    if (getsockopt(fd, SOL_SOCKET, SO_TYPE, &value, &len) != 0 || value != SOCK_STREAM) return 0;
+
+    sockaddr_storage addr;
+    len = sizeof(addr);
+    if (getsockname(fd, (sockaddr*)&addr, &len) != 0) return 0;
+    uint16_t port;
+    if (addr.ss_family == AF_INET) port = ntohs(((sockaddr_in*)&addr)->sin_port);
+    else if (addr.ss_family == AF_INET6) port = ntohs(((sockaddr_in6*)&addr)->sin6_port);
+    else return 0;
+    return InRange(port, first, count) ? port : 0;
+}
+#endif
+#endif
+
+#ifdef TRACY_ENABLE
+// must match the declarations in sys
+static_assert(sizeof(___tracy_source_location_data) == 3 * sizeof(void*) + 8, "source_location_data layout changed");
+static_assert(offsetof(___tracy_source_location_data, line) == 3 * sizeof(void*), "source_location_data layout changed");
+static_assert(offsetof(___tracy_source_location_data, color) == 3 * sizeof(void*) + 4, "source_location_data layout changed");
+static_assert(sizeof(___tracy_c_zone_context) == 8, "zone_context layout changed");
+static_assert(offsetof(___tracy_c_zone_context, active) == 4, "zone_context layout changed");
+#endif
+
+extern "C" {
+
+void ___tracy_rs_request_shutdown()
+{
+#ifdef TRACY_ENABLE
+    tracy::GetProfiler().RequestShutdown();
+#endif
+}
+
+int ___tracy_rs_shutdown_finished()
+{
+#ifdef TRACY_ENABLE
+    return tracy::GetProfiler().HasShutdownFinished() ? 1 : 0;
+#else
+    return 1;
+#endif
+}
+
+// 0 if the profiler isn't listening (yet)
+uint16_t ___tracy_rs_listen_port()
+{
+#ifdef TRACY_ENABLE
+    uint16_t first, count;
+    ListenPortRange(first, count);
+#  ifdef _WIN32
+    const auto port = FindListenPort<MIB_TCP6TABLE_OWNER_PID>(AF_INET6, first, count);
+    return port != 0 ? port : FindListenPort<MIB_TCPTABLE_OWNER_PID>(AF_INET, first, count);
+#  else
+#    ifdef __linux__
+    // only look at the open descriptors
+    if (DIR* dir = opendir("/proc/self/fd"))
+    {
+        const int dirFd = dirfd(dir);
+        uint16_t port = 0;
+        while (const dirent* entry = readdir(dir))
+        {
+            const int fd = atoi(entry->d_name);
+            if (entry->d_name[0] == '.' || fd == dirFd) continue;
+            if ((port = ListenPort(fd, first, count)) != 0) break;
+        }
+        closedir(dir);
+        return port;
+    }
+#    endif
+    const long maxFd = sysconf(_SC_OPEN_MAX);
+    for (int fd = 0; fd < (maxFd > 0 && maxFd < 65536 ? maxFd : 65536); fd++)
+    {
+        if (const uint16_t port = ListenPort(fd, first, count)) return port;
+    }
+    return 0;
+#  endif
+#else
+    return 0;
+#endif
+}
+
+void ___tracy_rs_source_callback_register(___tracy_rs_source_callback cb, void* data)
+{
+#if defined TRACY_ENABLE && !defined TRACY_NO_CODE_TRANSFER
+    s_sourceCallback = cb;
+    tracy::Profiler::SourceCallbackRegister(SourceCallback, data);
+#endif
+}
+
+// the source callback has to return memory from Tracy's allocator
+void* ___tracy_rs_malloc(size_t size)
+{
+#ifdef TRACY_ENABLE
+    return tracy::tracy_malloc_fast(size);
+#else
+    return nullptr;
+#endif
+}
+
+void ___tracy_rs_parameter_register(void (*cb)(void* data, uint32_t idx, int32_t val), void* data)
+{
+#ifdef TRACY_ENABLE
+    tracy::Profiler::ParameterRegister(cb, data);
+#endif
+}
+
+// the sizes of the events by type, including the type but not trailing strings; src/protocol.rs
+// checks its own against these
+const size_t* ___tracy_rs_queue_data_size(size_t* count)
+{
+    *count = sizeof(tracy::QueueDataSize) / sizeof(tracy::QueueDataSize[0]);
+    return tracy::QueueDataSize;
+}
+
+void ___tracy_rs_parameter_setup(uint32_t idx, const char* name, int isBool, int32_t val)
+{
+#ifdef TRACY_ENABLE
+    tracy::Profiler::ParameterSetup(idx, name, isBool != 0, val);
+#endif
+}
+
+}
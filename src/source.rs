@@ -0,0 +1,112 @@
+//! Serving source files to the viewer when they are not available at the paths recorded by `loc!`,
+//! e.g. for deployed binaries.
+
+use crate::backend::{Active, Backend};
+use std::{borrow::Cow, fs, path::{Path, PathBuf}};
+
+/// A source file embedded by `embed_sources!`.
+#[derive(Clone, Copy, Debug)]
+pub struct EmbeddedSource {
+    /// The path relative to the crate root, as passed to `embed_sources!`.
+    pub path: &'static str,
+    pub contents: &'static str,
+}
+
+/// Embeds source files of the calling crate, given relative to its root, for `embedded`.
+///
+/// ```ignore
+/// tracy_rs::source::register(tracy_rs::source::embedded(tracy_rs::embed_sources!["src/main.rs", "src/render.rs"]));
+/// ```
+#[cfg(feature = "embed_sources")]
+#[macro_export]
+macro_rules! embed_sources {
+    ($($path: expr),* $(,)?) => (&[$($crate::source::EmbeddedSource {
+        path: $path,
+        contents: include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/", $path)),
+    }),*]);
+}
+
+/// Registers `callback` to look up the contents of the source files the viewer asks for.
+///
+/// Registering another callback replaces the previous one, which is leaked.
+pub fn register<F>(callback: F)
+where
+    F: Fn(&str) -> Option<Cow<'static, [u8]>> + Send + Sync + 'static,
+{
+    Active::source_callback_register(Box::leak(Box::new(callback)));
+}
+
+/// Looks up files in `sources` by the end of their path, since `loc!` records them relative to the
+/// workspace root.
+pub fn embedded(sources: &'static [EmbeddedSource]) -> impl Fn(&str) -> Option<Cow<'static, [u8]>> + Send + Sync {
+    move |filename| {
+        sources.iter()
+            .find(|source| Path::new(filename).ends_with(source.path))
+            .map(|source| Cow::Borrowed(source.contents.as_bytes()))
+    }
+}
+
+/// Looks up files below `root`.
+///
+/// Relative paths are taken as relative to `root`. For absolute paths, e.g. from the machine the
+/// binary was built on, the longest trailing part of the path that exists below `root` is used.
+pub fn from_dir(root: impl Into<PathBuf>) -> impl Fn(&str) -> Option<Cow<'static, [u8]>> + Send + Sync {
+    let root = root.into();
+    move |filename| {
+        let path = Path::new(filename);
+        let mut components = path.components();
+        let mut candidates = Vec::new();
+        if path.is_relative() {
+            candidates.push(root.join(path));
+        } else {
+            while components.next().is_some() && !components.as_path().as_os_str().is_empty() {
+                candidates.push(root.join(components.as_path()));
+            }
+        }
+        candidates.into_iter()
+            .find_map(|candidate| fs::read(candidate).ok())
+            .map(Cow::Owned)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{env, process};
+
+    static SOURCES: &[EmbeddedSource] = &[
+        EmbeddedSource { path: "src/main.rs", contents: "fn main() {}" },
+        EmbeddedSource { path: "src/render/mod.rs", contents: "mod render;" },
+    ];
+
+    #[test]
+    fn embedded_suffix() {
+        let lookup = embedded(SOURCES);
+        let contents = |filename| lookup(filename).map(|contents| String::from_utf8(contents.into_owned()).unwrap());
+        assert_eq!(contents("src/main.rs").as_deref(), Some("fn main() {}"));
+        assert_eq!(contents("game/src/main.rs").as_deref(), Some("fn main() {}"));
+        assert_eq!(contents("/home/ci/game/src/render/mod.rs").as_deref(), Some("mod render;"));
+        //only whole components match
+        assert_eq!(contents("game/xsrc/main.rs"), None);
+        assert_eq!(contents("main.rs"), None);
+        assert_eq!(contents("src/lib.rs"), None);
+    }
+
+    #[test]
+    fn from_dir_remaps() {
+        let root = env::temp_dir().join(format!("tracy-rs-source-{}", process::id()));
+        fs::create_dir_all(root.join("src/render")).unwrap();
+        fs::write(root.join("src/main.rs"), "fn main() {}").unwrap();
+        fs::write(root.join("src/render/mod.rs"), "mod render;").unwrap();
+
+        let lookup = from_dir(&root);
+        let contents = |filename| lookup(filename).map(|contents| String::from_utf8(contents.into_owned()).unwrap());
+        assert_eq!(contents("src/main.rs").as_deref(), Some("fn main() {}"));
+        assert_eq!(contents("/home/ci/game/src/render/mod.rs").as_deref(), Some("mod render;"));
+        assert_eq!(contents("/home/ci/game/src/main.rs").as_deref(), Some("fn main() {}"));
+        assert_eq!(contents("src/lib.rs"), None);
+        assert_eq!(contents("/home/ci/game/src/lib.rs"), None);
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -0,0 +1,36 @@
+//! Raw bindings to the Tracy C API from TracyC.h.
+//!
+//! The bindings in src/sys/bindings.rs are written by hand in the form bindgen generates, with
+//! TRACY_ENABLE, TRACY_FIBERS and TRACY_MANUAL_LIFETIME defined. The functions behind the latter two
+//! only exist if the client is built with them. The "bindgen" feature generates the bindings from
+//! the headers of the client that is being built instead.
+
+#![allow(non_camel_case_types, non_upper_case_globals, non_snake_case, clippy::all)]
+
+use libc::{c_char, c_int, c_void, size_t};
+
+#[cfg(not(feature = "bindgen"))]
+include!("sys/bindings.rs");
+#[cfg(feature = "bindgen")]
+include!(concat!(env!("OUT_DIR"), "/bindings.rs"));
+
+pub type source_location_data = ___tracy_source_location_data;
+pub type zone_context = ___tracy_c_zone_context;
+
+// src/shim.cpp
+extern "C" {
+    pub fn ___tracy_rs_request_shutdown();
+    pub fn ___tracy_rs_shutdown_finished() -> c_int;
+    pub fn ___tracy_rs_listen_port() -> u16;
+    pub fn ___tracy_rs_source_callback_register(
+        cb: Option<unsafe extern "C" fn(data: *mut c_void, filename: *const c_char, size: *mut size_t) -> *mut c_char>,
+        data: *mut c_void,
+    );
+    pub fn ___tracy_rs_malloc(size: size_t) -> *mut c_void;
+    pub fn ___tracy_rs_parameter_register(
+        cb: Option<unsafe extern "C" fn(data: *mut c_void, idx: u32, val: i32)>,
+        data: *mut c_void,
+    );
+    pub fn ___tracy_rs_parameter_setup(idx: u32, name: *const c_char, isBool: c_int, val: i32);
+    pub fn ___tracy_rs_queue_data_size(count: *mut size_t) -> *const size_t;
+}
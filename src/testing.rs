@@ -0,0 +1,375 @@
+//! An in-process recorder that replaces the Tracy client with the "test_backend" feature.
+//!
+//! The recorder implements the parts of the C API used by this crate and records the events of
+//! the current thread inside `capture`, or of all threads inside `capture_threads`, so tests can
+//! assert on the instrumentation:
+//!
+//! ```
+//! use tracy_rs::{testing::{self, Event}, zone_n};
+//!
+//! let events = testing::capture(|| {
+//!     zone_n!("update", true);
+//! });
+//! assert_eq!(testing::zone_names(&events), ["update"]);
+//! assert!(matches!(events.last(), Some(Event::ZoneEnd { .. })));
+//! ```
+//!
+//! The recorder always reports a viewer as connected, so zones created with `when_connected` are
+//! recorded as well. Parameters and source callbacks are accepted and ignored.
+
+use crate::{sys, Color};
+use libc::{c_char, c_int, c_void, size_t};
+use std::{
+    cell::RefCell,
+    ffi::CStr,
+    slice,
+    sync::{atomic::{AtomicBool, AtomicU32, Ordering}, Mutex, MutexGuard, PoisonError},
+    thread::{self, ThreadId},
+};
+
+/// The source location of a recorded zone.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Location {
+    pub name: Option<String>,
+    pub function: String,
+    pub file: String,
+    pub line: u32,
+    pub color: Color,
+}
+
+/// An event emitted by the instrumentation.
+///
+/// Zone events carry the id of their zone, which is unique for the process.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Event {
+    ZoneBegin { id: u32, location: Location },
+    ZoneEnd { id: u32 },
+    ZoneText { id: u32, text: String },
+    ZoneColor { id: u32, color: Color },
+    ZoneValue { id: u32, value: u64 },
+    /// `None` for the main frame.
+    FrameMark { name: Option<String> },
+    FrameMarkStart { name: String },
+    FrameMarkEnd { name: String },
+    FrameImage { width: u16, height: u16, offset: u8, flip: bool },
+    Message { text: String, color: Option<Color> },
+    AppInfo { text: String },
+    Plot { name: String, value: f64 },
+    MemoryAlloc { ptr: usize, size: usize },
+    MemoryFree { ptr: usize },
+}
+
+thread_local! {
+    static EVENTS: RefCell<Option<Vec<Event>>> = const { RefCell::new(None) };
+    static THREAD_ID: ThreadId = thread::current().id();
+}
+
+static NEXT_ZONE_ID: AtomicU32 = AtomicU32::new(1);
+
+/// Whether `ALL_EVENTS` is recording; saves the lock while it isn't.
+static RECORDING_ALL: AtomicBool = AtomicBool::new(false);
+static ALL_EVENTS: Mutex<Option<Vec<(ThreadId, Event)>>> = Mutex::new(None);
+/// Held by `capture_threads`, so they run one at a time.
+static CAPTURING_ALL: Mutex<()> = Mutex::new(());
+
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Runs `f` and returns the events it emitted on the current thread.
+///
+/// Events from other threads are not recorded, so tests running in parallel don't see each
+/// other's events; use `capture_threads` for those. Nested captures only record into the innermost
+/// one.
+pub fn capture<F: FnOnce()>(f: F) -> Vec<Event> {
+    //restores the outer capture even if f panics
+    struct Restore(Option<Vec<Event>>);
+
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            let outer = self.0.take();
+            EVENTS.with(|events| *events.borrow_mut() = outer);
+        }
+    }
+
+    let mut restore = Restore(EVENTS.with(|events| events.borrow_mut().replace(Vec::new())));
+    f();
+    let captured = EVENTS.with(|events| events.replace(restore.0.take()));
+    captured.unwrap_or_default()
+}
+
+/// Runs `f` and returns the events emitted on all threads meanwhile, grouped by the thread that
+/// emitted them, in the order the threads emitted their first event.
+///
+/// Threads spawned by `f` are recorded until `f` returns, so join them inside `f`. Other threads,
+/// like those of tests running in parallel, are recorded as well, so look up the threads of
+/// interest by their id. Only one `capture_threads` runs at a time; `capture` still records the
+/// events of its thread inside of it.
+///
+/// ```
+/// use std::thread;
+/// use tracy_rs::{testing, zone_n};
+///
+/// let mut worker = None;
+/// let threads = testing::capture_threads(|| {
+///     let handle = thread::spawn(|| {
+///         zone_n!("work", true);
+///     });
+///     worker = Some(handle.thread().id());
+///     handle.join().unwrap();
+/// });
+/// let (_, events) = threads.iter().find(|(thread, _)| Some(*thread) == worker).unwrap();
+/// assert_eq!(testing::zone_names(events), ["work"]);
+/// ```
+pub fn capture_threads<F: FnOnce()>(f: F) -> Vec<(ThreadId, Vec<Event>)> {
+    //stops recording even if f panics
+    struct Stop;
+
+    impl Drop for Stop {
+        fn drop(&mut self) {
+            RECORDING_ALL.store(false, Ordering::Relaxed);
+            lock(&ALL_EVENTS).take();
+        }
+    }
+
+    let _capturing = lock(&CAPTURING_ALL);
+    *lock(&ALL_EVENTS) = Some(Vec::new());
+    RECORDING_ALL.store(true, Ordering::Relaxed);
+    let stop = Stop;
+    f();
+    let events = lock(&ALL_EVENTS).take().unwrap_or_default();
+    drop(stop);
+
+    let mut threads: Vec<(ThreadId, Vec<Event>)> = Vec::new();
+    for (thread, event) in events {
+        match threads.iter_mut().find(|(id, _)| *id == thread) {
+            Some((_, events)) => events.push(event),
+            None => threads.push((thread, vec![event])),
+        }
+    }
+    threads
+}
+
+/// The names of the zones begun in `events`, in order; unnamed zones use their function name.
+pub fn zone_names(events: &[Event]) -> Vec<&str> {
+    events.iter()
+        .filter_map(|event| match event {
+            Event::ZoneBegin { location, .. } => Some(location.name.as_ref().unwrap_or(&location.function).as_str()),
+            _ => None,
+        })
+        .collect()
+}
+
+fn record(event: impl FnOnce() -> Event) {
+    let recording_all = RECORDING_ALL.load(Ordering::Relaxed);
+    let capturing = EVENTS.with(|events| events.borrow().is_some());
+    if !recording_all && !capturing {
+        return;
+    }
+
+    let event = event();
+    if recording_all {
+        //threads that are exiting have no id anymore
+        if let Ok(thread) = THREAD_ID.try_with(|id| *id) {
+            if let Some(events) = lock(&ALL_EVENTS).as_mut() {
+                events.push((thread, event.clone()));
+            }
+        }
+    }
+    if capturing {
+        EVENTS.with(|events| {
+            if let Some(events) = events.borrow_mut().as_mut() {
+                events.push(event);
+            }
+        });
+    }
+}
+
+unsafe fn string(ptr: *const c_char) -> String {
+    CStr::from_ptr(ptr).to_string_lossy().into_owned()
+}
+
+unsafe fn string_with_len(ptr: *const c_char, len: size_t) -> String {
+    String::from_utf8_lossy(slice::from_raw_parts(ptr as *const u8, len)).into_owned()
+}
+
+unsafe fn begin_zone(srcloc: *const sys::source_location_data, active: c_int) -> sys::zone_context {
+    let context = sys::zone_context { id: NEXT_ZONE_ID.fetch_add(1, Ordering::Relaxed), active };
+    if active != 0 {
+        let srcloc = &*srcloc;
+        record(|| Event::ZoneBegin {
+            id: context.id,
+            location: Location {
+                name: if srcloc.name.is_null() { None } else { Some(string(srcloc.name)) },
+                function: string(srcloc.function),
+                file: string(srcloc.file),
+                line: srcloc.line,
+                color: Color(srcloc.color),
+            },
+        });
+    }
+    context
+}
+
+fn record_zone(context: sys::zone_context, event: impl FnOnce(u32) -> Event) {
+    if context.active != 0 {
+        record(|| event(context.id));
+    }
+}
+
+#[no_mangle]
+unsafe extern "C" fn ___tracy_emit_zone_begin(srcloc: *const sys::source_location_data, active: c_int) -> sys::zone_context {
+    begin_zone(srcloc, active)
+}
+
+#[no_mangle]
+unsafe extern "C" fn ___tracy_emit_zone_begin_callstack(srcloc: *const sys::source_location_data, _depth: c_int, active: c_int) -> sys::zone_context {
+    begin_zone(srcloc, active)
+}
+
+#[no_mangle]
+extern "C" fn ___tracy_emit_zone_end(ctx: sys::zone_context) {
+    record_zone(ctx, |id| Event::ZoneEnd { id });
+}
+
+#[no_mangle]
+unsafe extern "C" fn ___tracy_emit_zone_text(ctx: sys::zone_context, txt: *const c_char, size: size_t) {
+    record_zone(ctx, |id| Event::ZoneText { id, text: string_with_len(txt, size) });
+}
+
+#[no_mangle]
+extern "C" fn ___tracy_emit_zone_color(ctx: sys::zone_context, color: u32) {
+    record_zone(ctx, |id| Event::ZoneColor { id, color: Color(color) });
+}
+
+#[no_mangle]
+extern "C" fn ___tracy_emit_zone_value(ctx: sys::zone_context, value: u64) {
+    record_zone(ctx, |id| Event::ZoneValue { id, value });
+}
+
+#[no_mangle]
+unsafe extern "C" fn ___tracy_emit_frame_mark(name: *const c_char) {
+    record(|| Event::FrameMark { name: if name.is_null() { None } else { Some(string(name)) } });
+}
+
+#[no_mangle]
+unsafe extern "C" fn ___tracy_emit_frame_mark_start(name: *const c_char) {
+    record(|| Event::FrameMarkStart { name: string(name) });
+}
+
+#[no_mangle]
+unsafe extern "C" fn ___tracy_emit_frame_mark_end(name: *const c_char) {
+    record(|| Event::FrameMarkEnd { name: string(name) });
+}
+
+#[no_mangle]
+extern "C" fn ___tracy_emit_frame_image(_image: *const c_void, w: u16, h: u16, offset: u8, flip: c_int) {
+    record(|| Event::FrameImage { width: w, height: h, offset, flip: flip != 0 });
+}
+
+#[no_mangle]
+unsafe extern "C" fn ___tracy_emit_message(txt: *const c_char, size: size_t, _callstack_depth: c_int) {
+    record(|| Event::Message { text: string_with_len(txt, size), color: None });
+}
+
+#[no_mangle]
+unsafe extern "C" fn ___tracy_emit_messageC(txt: *const c_char, size: size_t, color: u32, _callstack_depth: c_int) {
+    record(|| Event::Message { text: string_with_len(txt, size), color: Some(Color(color)) });
+}
+
+#[no_mangle]
+unsafe extern "C" fn ___tracy_emit_message_appinfo(txt: *const c_char, size: size_t) {
+    record(|| Event::AppInfo { text: string_with_len(txt, size) });
+}
+
+#[no_mangle]
+unsafe extern "C" fn ___tracy_emit_plot(name: *const c_char, val: f64) {
+    record(|| Event::Plot { name: string(name), value: val });
+}
+
+#[no_mangle]
+extern "C" fn ___tracy_connected() -> c_int {
+    1
+}
+
+#[no_mangle]
+extern "C" fn ___tracy_emit_memory_alloc(ptr: *const c_void, size: size_t, _secure: c_int) {
+    record(|| Event::MemoryAlloc { ptr: ptr as usize, size });
+}
+
+#[no_mangle]
+extern "C" fn ___tracy_emit_memory_alloc_callstack(ptr: *const c_void, size: size_t, _depth: c_int, _secure: c_int) {
+    record(|| Event::MemoryAlloc { ptr: ptr as usize, size });
+}
+
+#[no_mangle]
+extern "C" fn ___tracy_emit_memory_free(ptr: *const c_void, _secure: c_int) {
+    record(|| Event::MemoryFree { ptr: ptr as usize });
+}
+
+#[no_mangle]
+extern "C" fn ___tracy_emit_memory_free_callstack(ptr: *const c_void, _depth: c_int, _secure: c_int) {
+    record(|| Event::MemoryFree { ptr: ptr as usize });
+}
+
+// src/shim.cpp
+
+#[no_mangle]
+extern "C" fn ___tracy_rs_request_shutdown() {}
+
+#[no_mangle]
+extern "C" fn ___tracy_rs_shutdown_finished() -> c_int {
+    1
+}
+
+#[no_mangle]
+extern "C" fn ___tracy_rs_listen_port() -> u16 {
+    0
+}
+
+#[no_mangle]
+extern "C" fn ___tracy_rs_source_callback_register(
+    _cb: Option<unsafe extern "C" fn(data: *mut c_void, filename: *const c_char, size: *mut size_t) -> *mut c_char>,
+    _data: *mut c_void,
+) {}
+
+#[no_mangle]
+unsafe extern "C" fn ___tracy_rs_malloc(size: size_t) -> *mut c_void {
+    libc::malloc(size)
+}
+
+#[no_mangle]
+extern "C" fn ___tracy_rs_parameter_register(_cb: Option<unsafe extern "C" fn(data: *mut c_void, idx: u32, val: i32)>, _data: *mut c_void) {}
+
+#[no_mangle]
+extern "C" fn ___tracy_rs_parameter_setup(_idx: u32, _name: *const c_char, _is_bool: c_int, _val: i32) {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::zone_n;
+
+    #[test]
+    fn captures_spawned_threads() {
+        let mut worker = None;
+        let mut own = Vec::new();
+        let threads = capture_threads(|| {
+            own = capture(|| {
+                zone_n!("spawn", true);
+                let handle = thread::spawn(|| {
+                    zone_n!("work", true);
+                });
+                worker = Some(handle.thread().id());
+                handle.join().unwrap();
+            });
+        });
+        let names = |thread| threads.iter()
+            .find(|(id, _)| *id == thread)
+            .map(|(_, events)| zone_names(events))
+            .unwrap_or_default();
+        assert_eq!(names(thread::current().id()), ["spawn"]);
+        assert_eq!(names(worker.unwrap()), ["work"]);
+        //capture still only sees its own thread
+        assert_eq!(zone_names(&own), ["spawn"]);
+    }
+}
@@ -0,0 +1,56 @@
+//! The panic hook replaces the process-wide hook, so it is tested in its own test binary.
+
+#![cfg(feature = "test_backend")]
+
+use std::{panic, sync::Once, time::Duration};
+use tracy_rs::{testing::{self, Event}, zone_n};
+
+fn install_hook() {
+    static INSTALL: Once = Once::new();
+    INSTALL.call_once(|| tracy_rs::panic::install_hook(false, Duration::ZERO));
+}
+
+fn ends(events: &[Event]) -> Vec<u32> {
+    events.iter().filter_map(|event| match event {
+        Event::ZoneEnd { id } => Some(*id),
+        _ => None,
+    }).collect()
+}
+
+fn begins(events: &[Event]) -> Vec<u32> {
+    events.iter().filter_map(|event| match event {
+        Event::ZoneBegin { id, .. } => Some(*id),
+        _ => None,
+    }).collect()
+}
+
+#[test]
+fn early_drop() {
+    install_hook();
+    let events = testing::capture(|| {
+        zone_n!(let outer = "outer", true);
+        zone_n!(let inner = "inner", true);
+        drop(outer);
+        drop(inner);
+    });
+    let begins = begins(&events);
+    assert_eq!(ends(&events), begins);
+}
+
+#[test]
+fn ends_open_zones() {
+    install_hook();
+    let events = testing::capture(|| {
+        let result = panic::catch_unwind(|| {
+            zone_n!("outer", true);
+            zone_n!("inner", true);
+            panic!("boom");
+        });
+        assert!(result.is_err());
+    });
+    let mut begins = begins(&events);
+    begins.reverse();
+    //ended once by the hook, not again while unwinding
+    assert_eq!(ends(&events), begins);
+    assert!(events.iter().any(|event| matches!(event, Event::Message { text, .. } if text.starts_with("panic: boom"))));
+}
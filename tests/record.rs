@@ -0,0 +1,93 @@
+//! Records an instrumented process with tracy-record and reads the recording back, which checks
+//! the protocol against the client this crate is built with.
+//!
+//! The instrumented process is this test binary, running only `instrumented_process`.
+
+#![cfg(all(feature = "enable", not(any(feature = "test_backend", feature = "chrome_trace", feature = "folded_stacks"))))]
+
+use std::{
+    env,
+    fs,
+    net::TcpListener,
+    process::{Command, Stdio},
+    thread,
+    time::{Duration, Instant},
+};
+use tracy_rs::{cstr, file::Recording, frame_mark, zone_n};
+
+/// Set for the instrumented process.
+const INSTRUMENTED: &str = "TRACY_RS_TEST_INSTRUMENTED";
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+#[test]
+fn instrumented_process() {
+    if env::var_os(INSTRUMENTED).is_none() {
+        return;
+    }
+    assert!(tracy_rs::wait_for_connection(TIMEOUT));
+    tracy_rs::app_info("recorded by tests/record.rs");
+    for i in 0..3 {
+        zone_n!("outer", true);
+        {
+            zone_n!("inner", true);
+            tracy_rs::plot(cstr!("iteration"), i as f64);
+        }
+        frame_mark!();
+    }
+    tracy_rs::message("done");
+    assert!(tracy_rs::flush_and_wait(TIMEOUT));
+}
+
+#[test]
+fn record_instrumented_process() {
+    //a port that is most likely still free when the client starts listening
+    let port = TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap().port();
+    let mut instrumented = Command::new(env::current_exe().unwrap())
+        .args(["--exact", "instrumented_process", "--test-threads", "1"])
+        .env(INSTRUMENTED, "1")
+        .env("TRACY_PORT", port.to_string())
+        .stdout(Stdio::null())
+        .spawn()
+        .unwrap();
+
+    let output = env::temp_dir().join(format!("tracy-rs-record-{}.recording", std::process::id()));
+    let start = Instant::now();
+    //the client listens shortly after the process starts
+    let recorded = loop {
+        let status = Command::new(env!("CARGO_BIN_EXE_tracy-record"))
+            .args(["-f", "-p", &port.to_string(), "-o"])
+            .arg(&output)
+            .status()
+            .unwrap();
+        if status.success() || start.elapsed() >= TIMEOUT {
+            break status.success();
+        }
+        thread::sleep(Duration::from_millis(100));
+    };
+    let instrumented = instrumented.wait().unwrap();
+    let recording = Recording::open(&output);
+    let _ = fs::remove_file(&output);
+    assert!(recorded);
+    assert!(instrumented.success());
+    let recording = recording.unwrap();
+
+    assert_eq!(recording.skipped_frames, 0);
+    assert_eq!(recording.app_info, ["recorded by tests/record.rs"]);
+    let thread = recording.threads.iter().find(|thread| !thread.zones.is_empty()).unwrap();
+    let zones: Vec<_> = thread.zones.iter()
+        .map(|zone| (recording.source_locations[zone.location].name(), zone.depth, zone.end.is_some()))
+        .collect();
+    assert_eq!(zones, [
+        ("outer", 0, true), ("inner", 1, true),
+        ("outer", 0, true), ("inner", 1, true),
+        ("outer", 0, true), ("inner", 1, true),
+    ]);
+    let location = &recording.source_locations[thread.zones[0].location];
+    assert!(location.file.ends_with("record.rs"));
+    assert_ne!(location.line, 0);
+    assert_eq!(recording.frames.iter().filter(|frame| frame.name.is_none()).count(), 2);
+    assert!(recording.messages.iter().any(|message| message.text == "done"));
+    let plot = recording.plots.iter().find(|plot| plot.name == "iteration").unwrap();
+    let values: Vec<_> = plot.points.iter().map(|&(_, value)| value).collect();
+    assert_eq!(values, [0.0, 1.0, 2.0]);
+}